@@ -6,7 +6,45 @@ pub const U32_LENGTH: usize = 4;
 pub const U64_LENGTH: usize = 8;
 pub const U128_LENGTH: usize = 16;
 pub const BOOL_LENGTH: usize = 1;
-//pub const OPTION_LENGTH: usize = 1;
+pub const OPTION_LENGTH: usize = 1;
+
+// Scaling factor for the staking reward-per-token accumulator
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// Maximum number of proposals that can be voted on in one batch
+pub const MAX_BATCH_VOTES: usize = 10;
+
+// Maximum length of a vote rationale's text or URI
+pub const MAX_RATIONALE_LENGTH: usize = 200;
+
+// Maximum number of recipients of a split bounty
+pub const MAX_SPLIT_RECIPIENTS: usize = 10;
+
+// Maximum length of a sub-treasury's name
+pub const MAX_SUB_TREASURY_NAME_LENGTH: usize = 32;
+
+// Maximum number of stewards of a sub-treasury
+pub const MAX_STEWARDS: usize = 5;
+
+// Maximum number of keys on the council
+pub const MAX_COUNCIL_MEMBERS: usize = 5;
+
+// Longest pause the guardian can set, in slots (about 2 days)
+pub const MAX_PAUSE_DURATION: u64 = 432_000;
+
+// Slots after a pause ends before the same target can be paused again (about 2 days)
+pub const PAUSE_COOLDOWN: u64 = 432_000;
+
+// Size of the signed vote nonce range each membership gets
+pub const NONCES_PER_MEMBERSHIP: u64 = 1 << 32;
+
+// Member roles, combined into a bitmask on MemberState
+pub const ROLE_ADMIN: u8 = 1 << 0; // Can create sub-treasuries
+pub const ROLE_COUNCIL: u8 = 1 << 1; // Can veto succeeded proposals
+pub const ROLE_STEWARD: u8 = 1 << 2; // Can spend from sub-treasuries they're a steward of
+pub const ROLE_REVIEWER: u8 = 1 << 3; // Can approve milestones they're the reviewer of
+pub const ROLE_CONTRIBUTOR: u8 = 1 << 4; // Recognized contributor
+pub const ALL_ROLES: u8 = ROLE_ADMIN | ROLE_COUNCIL | ROLE_STEWARD | ROLE_REVIEWER | ROLE_CONTRIBUTOR;
 
 // Base voting points
 pub const BASE_VOTE_POINTS: u64 = 10;
@@ -26,6 +64,9 @@ pub const VOTE_REPUTATION_INCREASE: i64 = 1;
 //Reputation decrease for removing a vote
 pub const VOTE_REPUTATION_DECREASE: i64 = -2;
 
+// Reputation decrease for leaving a sealed vote unrevealed
+pub const UNREVEALED_COMMIT_REPUTATION_DECREASE: i64 = -5;
+
 // Reputation increase for creating a proposal
 pub const PROPOSAL_CREATION_REPUTATION_INCREASE: i64 = 5;
 
@@ -38,6 +79,9 @@ pub const MIN_REPUTATION_FOR_PROPOSAL: u64 = 100;
 // Maximum reputation score
 pub const MAX_REPUTATION_SCORE: u64 = 10000;
 
+// Denominator for coefficients expressed in basis points
+pub const BASIS_POINTS: u64 = 10000;
+
 // Decay factor for reputation (e.g., 5% decay per month)
 pub const REPUTATION_DECAY_FACTOR: f64 = 0.95;
 
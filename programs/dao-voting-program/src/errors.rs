@@ -39,5 +39,123 @@ pub enum DaoError {
     #[msg("Failed to deserialize Proposal")]
     DeserializationError,
     #[msg("Failed to serialize Proposal")]
-    SerializationError
+    SerializationError,
+    #[msg("Missing or invalid ed25519 signature instruction")]
+    InvalidSignatureInstruction,
+    #[msg("Signed vote message doesn't match the vote")]
+    InvalidVoteMessage,
+    #[msg("Invalid nonce")]
+    InvalidNonce,
+    #[msg("Invalid ballot mode for this proposal")]
+    InvalidBallotMode,
+    #[msg("Proposal is not in its reveal window")]
+    NotInRevealWindow,
+    #[msg("Revealed vote doesn't match the commitment")]
+    InvalidCommitment,
+    #[msg("Invalid batch vote accounts")]
+    InvalidBatch,
+    #[msg("Invalid rationale")]
+    InvalidRationale,
+    #[msg("Missing or invalid voter weight record")]
+    InvalidVoterWeightRecord,
+    #[msg("Voter weight record expired")]
+    VoterWeightExpired,
+    #[msg("NFT is not a verified member of the DAO's collection")]
+    InvalidNft,
+    #[msg("Instruction not supported by this DAO's governance mode")]
+    InvalidGovernanceMode,
+    #[msg("There are no stakers to distribute rewards to")]
+    NoStakers,
+    #[msg("Claim your rewards before closing the stake account")]
+    UnclaimedRewards,
+    #[msg("No rewards to claim")]
+    NoRewards,
+    #[msg("Missing reward pool account")]
+    MissingRewardPool,
+    #[msg("Stake position is locked")]
+    StakeLocked,
+    #[msg("Vote doesn't belong to this stake position")]
+    InvalidStakePosition,
+    #[msg("Tokens are committed to open votes")]
+    StakeCommitted,
+    #[msg("Missing or invalid staking receipt account")]
+    InvalidReceiptAccount,
+    #[msg("A lock can only be set when creating the stake position")]
+    InvalidLockPeriod,
+    #[msg("Invalid proposal bond settings")]
+    InvalidBondConfig,
+    #[msg("Missing or invalid proposal bond accounts")]
+    InvalidBondAccount,
+    #[msg("Invalid proposal fee refund policy")]
+    InvalidFeeRefundPolicy,
+    #[msg("Missing proposer account")]
+    MissingProposer,
+    #[msg("Missing or invalid token bounty accounts")]
+    InvalidTokenBountyAccount,
+    #[msg("Invalid milestone bounty")]
+    InvalidMilestoneBounty,
+    #[msg("Signer is not the bounty's reviewer")]
+    InvalidReviewer,
+    #[msg("Invalid payment stream")]
+    InvalidPaymentStream,
+    #[msg("Missing or invalid payment stream accounts")]
+    InvalidStreamAccount,
+    #[msg("Nothing to withdraw yet")]
+    NothingToWithdraw,
+    #[msg("Invalid bounty split")]
+    InvalidBountySplit,
+    #[msg("Invalid treasury spending limits")]
+    InvalidSpendingLimits,
+    #[msg("Treasury spending limit exceeded")]
+    SpendingLimitExceeded,
+    #[msg("Payout would take the treasury below its reserve floor")]
+    ReserveFloorBreached,
+    #[msg("Invalid sub-treasury")]
+    InvalidSubTreasury,
+    #[msg("Signer is not a steward of the sub-treasury")]
+    InvalidSteward,
+    #[msg("Sub-treasury budget exceeded")]
+    BudgetExceeded,
+    #[msg("Sub-treasury budget period has ended")]
+    BudgetPeriodEnded,
+    #[msg("Sub-treasury budget period is still active")]
+    BudgetPeriodActive,
+    #[msg("Invalid ragequit amount")]
+    InvalidRagequitAmount,
+    #[msg("Invalid ragequit token account")]
+    InvalidRagequitAccount,
+    #[msg("Signer is not the DAO's guardian")]
+    InvalidGuardian,
+    #[msg("Invalid pause duration")]
+    InvalidPauseDuration,
+    #[msg("Pause cooldown has not elapsed")]
+    PauseCooldown,
+    #[msg("Not paused")]
+    NotPaused,
+    #[msg("Paused by the guardian")]
+    DaoPaused,
+    #[msg("Invalid council")]
+    InvalidCouncil,
+    #[msg("Signer is not on the council")]
+    InvalidCouncilMember,
+    #[msg("Veto window has closed")]
+    VetoWindowClosed,
+    #[msg("Veto window is still open")]
+    VetoWindowOpen,
+    #[msg("Invalid roles")]
+    InvalidRoles,
+    #[msg("Member doesn't have the required role")]
+    MissingRole,
+    #[msg("Lock period exceeds the DAO's maximum")]
+    LockPeriodTooLong,
+    #[msg("Only positions staked by someone else can be rejected")]
+    InvalidStakeFunder,
+    #[msg("Voting on the proposal has already started")]
+    VotingStarted,
+    #[msg("Invalid steward set")]
+    InvalidStewardSet,
+    #[msg("Signer is not the DAO's NFT weight authority")]
+    InvalidNftWeightAuthority,
+    #[msg("Invalid NFT weight")]
+    InvalidNftWeight,
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+use crate::state::VoteType;
+
+#[event]
+pub struct VoteRationaleRecorded {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub vote_type: VoteType,
+    pub uri: String,
+    pub content_hash: [u8; 32],
+}
@@ -0,0 +1,168 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    constants::MAX_BATCH_VOTES,
+    errors::DaoError,
+    instructions::{cast_vote, freeze_receipts, record_rationale},
+    utils::create_pda_account,
+    state::{setup::DaoSetup, BatchVoteItem, MemberState, Proposal, StakeState, VoteState, VoterWeightAction},
+};
+
+// Votes on several proposals at once. Each item is matched with a
+// [proposal, vote] pair of writable accounts in `remaining_accounts`.
+#[derive(Accounts)]
+pub struct BatchVote<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump,
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    /// Required with liquid staking, to freeze the receipts voted with
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Option<Program<'info, Token>>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> BatchVote<'info> {
+    pub fn batch_vote(
+        &mut self,
+        items: Vec<BatchVoteItem>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            !items.is_empty() && items.len() <= MAX_BATCH_VOTES,
+            DaoError::InvalidBatch
+        );
+        require!(
+            remaining_accounts.len() == items.len() * 2,
+            DaoError::InvalidBatch
+        );
+
+        // Each proposal can only be voted on once per batch
+        let mut voted: Vec<Pubkey> = Vec::with_capacity(items.len());
+        for (item, accounts) in items.iter().zip(remaining_accounts.chunks(2)) {
+            require!(!voted.contains(accounts[0].key), DaoError::InvalidBatch);
+            voted.push(accounts[0].key());
+            self.vote_one(item, &accounts[0], &accounts[1])?;
+        }
+
+        // Keep the receipts voted with from being voted with again
+        freeze_receipts(
+            &self.config,
+            self.token_program.as_ref(),
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+        )
+    }
+
+    fn vote_one(
+        &mut self,
+        item: &BatchVoteItem,
+        proposal_info: &AccountInfo<'info>,
+        vote_info: &AccountInfo<'info>,
+    ) -> Result<()> {
+        require!(
+            proposal_info.is_writable && vote_info.is_writable,
+            DaoError::InvalidBatch
+        );
+
+        // Load the proposal and make sure it belongs to this DAO
+        let mut proposal: Account<'info, Proposal> = Account::try_from(proposal_info)?;
+        let proposal_key = Pubkey::create_program_address(
+            &[
+                b"proposal",
+                self.config.key().as_ref(),
+                proposal.id.to_le_bytes().as_ref(),
+                &[proposal.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| DaoError::InvalidBatch)?;
+        require_keys_eq!(proposal_info.key(), proposal_key, DaoError::InvalidBatch);
+
+        // The vote account must be the owner's vote PDA for this proposal
+        let (vote_key, bump) = Pubkey::find_program_address(
+            &[b"vote", proposal_info.key.as_ref(), self.owner.key.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(vote_info.key(), vote_key, DaoError::InvalidBatch);
+
+        // Create the vote account. This fails if the owner already voted on the proposal.
+        let seeds = &[
+            &b"vote"[..],
+            &proposal_info.key.to_bytes()[..],
+            &self.owner.key.to_bytes()[..],
+            &[bump],
+        ];
+        create_pda_account(
+            &self.owner.to_account_info(),
+            vote_info,
+            VoteState::LEN,
+            &self.system_program.to_account_info(),
+            &[&seeds[..]],
+        )?;
+        vote_info.try_borrow_mut_data()?[..8].copy_from_slice(&VoteState::DISCRIMINATOR);
+        let mut vote: Account<'info, VoteState> = Account::try_from(vote_info)?;
+
+        let voting_power = self.config.voting_power(
+            self.config.key(),
+            &self.stake_state,
+            self.voter_weight_record.as_deref(),
+            VoterWeightAction::CastVote,
+            Some(proposal_info.key()),
+            self.receipt_account.as_deref(),
+        )?;
+
+        cast_vote(
+            &self.config,
+            &mut proposal,
+            &mut self.stake_state,
+            &mut vote,
+            &mut self.member_state,
+            self.owner.key(),
+            item.amount,
+            item.vote_type,
+            voting_power,
+            bump,
+        )?;
+
+        if let Some(rationale) = item.rationale.clone() {
+            record_rationale(&mut vote, proposal_info.key(), rationale)?;
+        }
+
+        // Persist both accounts, as Anchor only does this for the accounts struct
+        proposal.exit(&crate::ID)?;
+        vote.exit(&crate::ID)
+    }
+}
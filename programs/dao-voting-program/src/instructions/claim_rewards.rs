@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DaoError,
+    state::{setup::DaoSetup, RewardPool, StakeState},
+};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Account<'info, RewardPool>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimRewards<'info> {
+    pub fn claim_rewards(&mut self) -> Result<()> {
+        // Settle rewards earned since the last stake change
+        let reward_per_token = self.reward_pool.reward_per_token;
+        self.stake_state.settle_rewards(reward_per_token)?;
+        self.stake_state.reset_reward_debt(reward_per_token)?;
+
+        let rewards = self.stake_state.take_rewards()?;
+        self.reward_pool.claim(rewards)?;
+
+        pay_rewards(
+            &self.reward_pool.to_account_info(),
+            &self.owner.to_account_info(),
+            rewards,
+        )
+    }
+}
+
+// The reward pool is owned by this program, so lamports can be moved directly
+pub fn pay_rewards(pool_info: &AccountInfo, owner_info: &AccountInfo, rewards: u64) -> Result<()> {
+    **pool_info.try_borrow_mut_lamports()? = pool_info
+        .lamports()
+        .checked_sub(rewards)
+        .ok_or(DaoError::Underflow)?;
+    **owner_info.try_borrow_mut_lamports()? = owner_info
+        .lamports()
+        .checked_add(rewards)
+        .ok_or(DaoError::Overflow)?;
+
+    Ok(())
+}
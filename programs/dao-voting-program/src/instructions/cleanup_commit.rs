@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{BASE_VOTE_POINTS, UNREVEALED_COMMIT_REPUTATION_DECREASE},
+    errors::DaoError,
+    state::{setup::DaoSetup, MemberState, Proposal, StakeState, VoteCommitment},
+};
+
+#[derive(Accounts)]
+pub struct CleanupCommit<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    /// CHECK: This account is closed once the proposal is executed or cleaned up. If not, it is deserialized and checked against the DAO in Proposal::load
+    proposal: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds=[b"commit", proposal.key().as_ref(), owner.key().as_ref()],
+        bump = commitment.bump,
+        constraint = commitment.dao == config.key() && commitment.position == stake_state.index @ DaoError::InvalidStakePosition
+    )]
+    commitment: Account<'info, VoteCommitment>,
+    #[account(
+        mut,
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump,
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> CleanupCommit<'info> {
+    pub fn cleanup_commit(&mut self) -> Result<()> {
+        // Unrevealed commits can only be discarded once the reveal window has closed.
+        // A closed proposal was executed or cleaned up, so its reveal window is over.
+        if let Some(mut proposal) = Proposal::load(&self.proposal, self.config.key())? {
+            if proposal.is_open().is_ok() && proposal.check_reveal_ended().is_err() {
+                return err!(DaoError::InvalidProposalStatus);
+            }
+        }
+
+        // Release the stake locked by the commitment
+        self.stake_state.remove_account()?;
+        self.member_state.remove_open_vote()?;
+
+        if self.commitment.slash_unrevealed {
+            // Slash reward points and reputation, and forfeit the rent to the treasury
+            self.member_state.slash_vote_points(BASE_VOTE_POINTS)?;
+            self.member_state
+                .update_reputation(UNREVEALED_COMMIT_REPUTATION_DECREASE)?;
+            self.commitment.close(self.treasury.to_account_info())
+        } else {
+            self.commitment.close(self.owner.to_account_info())
+        }
+    }
+}
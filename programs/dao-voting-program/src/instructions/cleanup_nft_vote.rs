@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::{
+    errors::DaoError,
+    state::{NftVoteRecord, Proposal},
+};
+
+#[derive(Accounts)]
+pub struct CleanupNftVote<'info> {
+    #[account(mut)]
+    voter: Signer<'info>,
+    /// CHECK: This account may already be closed. If not, it is deserialized and checked in cleanup_nft_vote
+    proposal: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = voter,
+        has_one = voter,
+        seeds=[b"nft_vote", proposal.key().as_ref(), nft_vote_record.nft_mint.as_ref()],
+        bump = nft_vote_record.bump
+    )]
+    nft_vote_record: Account<'info, NftVoteRecord>,
+}
+
+impl<'info> CleanupNftVote<'info> {
+    // Reclaims the rent of an NFT vote record once the proposal can no longer be voted on
+    pub fn cleanup_nft_vote(&mut self) -> Result<()> {
+        // A closed proposal can't be voted on again, as proposal IDs are never reused
+        if self.proposal.data_is_empty() {
+            return Ok(());
+        }
+        let mut proposal: Account<Proposal> = Account::try_from(&self.proposal)?;
+        if proposal.is_open().is_ok() && proposal.check_expiry().is_ok() {
+            return err!(DaoError::InvalidProposalStatus);
+        }
+        Ok(())
+    }
+}
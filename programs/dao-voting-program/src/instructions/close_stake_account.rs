@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{token::{Token, TokenAccount, Mint, CloseAccount, close_account}, associated_token::AssociatedToken};
 
-use crate::{state::{setup::DaoSetup, StakeState}, errors::DaoError};
+use crate::{state::{setup::DaoSetup, MemberState, StakeState}, errors::DaoError};
 
 #[derive(Accounts)]
 pub struct CloseStakeAccount<'info> {
@@ -9,7 +9,7 @@ pub struct CloseStakeAccount<'info> {
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref()],
+        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
         bump = stake_state.vault_bump,
         token::mint = mint,
         token::authority = stake_auth
@@ -29,10 +29,16 @@ pub struct CloseStakeAccount<'info> {
     #[account(
         mut,
         close = owner,
-        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref()],
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
         bump = stake_state.state_bump
     )]
     stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds = [b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump
+    )]
+    member_state: Account<'info, MemberState>,
     #[account(
         seeds=[b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
@@ -47,39 +53,54 @@ impl<'info> CloseStakeAccount<'info> {
     pub fn cleanup(
         &mut self
     ) -> Result<()> {
-        self.close_stake_ata()?;
-        match self.stake_state.check_stake() {
-            Ok(_) => err!(DaoError::InvalidStakeAmount),
-            Err(_) => Ok(())
+        self.stake_state.check_rewards_claimed()?;
+        self.stake_state.check_accounts()?;
+        if self.stake_state.check_stake().is_ok() {
+            return err!(DaoError::InvalidStakeAmount);
         }
+        self.member_state.remove_stake_position()?;
+        close_stake_vault(
+            &self.token_program,
+            &self.stake_ata,
+            self.owner.to_account_info(),
+            self.stake_auth.to_account_info(),
+            self.config.key(),
+            self.owner.key(),
+            self.stake_state.auth_bump,
+        )
     }
+}
 
-    pub fn close_stake_ata(
-        &self
-    ) -> Result<()> {
-        let accounts = CloseAccount {
-            account: self.stake_ata.to_account_info(),
-            destination: self.owner.to_account_info(),
-            authority: self.stake_auth.to_account_info()
-        };
-
-        let seeds = &[
-            &b"auth"[..],
-            &self.config.key().to_bytes()[..],
-            &self.owner.key().to_bytes()[..],
-            &[self.stake_state.auth_bump],
-        ];
-
-        let signer_seeds = &[&seeds[..]];
+// Closes an empty stake vault, refunding its rent to the destination
+pub fn close_stake_vault<'info>(
+    token_program: &Program<'info, Token>,
+    stake_ata: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    stake_auth: AccountInfo<'info>,
+    config: Pubkey,
+    owner: Pubkey,
+    auth_bump: u8,
+) -> Result<()> {
+    let accounts = CloseAccount {
+        account: stake_ata.to_account_info(),
+        destination,
+        authority: stake_auth
+    };
 
-        let ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(), 
-            accounts, 
-            signer_seeds
-        );
+    let seeds = &[
+        &b"auth"[..],
+        &config.to_bytes()[..],
+        &owner.to_bytes()[..],
+        &[auth_bump],
+    ];
 
-        close_account(ctx)
-    }
+    let signer_seeds = &[&seeds[..]];
 
+    let ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(), 
+        accounts, 
+        signer_seeds
+    );
 
-}
\ No newline at end of file
+    close_account(ctx)
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    errors::DaoError,
+    instructions::freeze_receipts,
+    state::{setup::DaoSetup, BallotMode, MemberState, PauseTarget, Proposal, StakeState, VoteCommitment, VoterWeightAction},
+};
+
+#[derive(Accounts)]
+pub struct CommitVote<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = owner,
+        seeds=[b"commit", proposal.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = VoteCommitment::LEN
+    )]
+    commitment: Account<'info, VoteCommitment>,
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump,
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    /// Required with liquid staking, to freeze the receipts voted with
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Option<Program<'info, Token>>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> CommitVote<'info> {
+    pub fn commit_vote(
+        &mut self,
+        commitment: [u8; 32],
+        bump: u8
+    ) -> Result<()> {
+        // Check the guardian hasn't paused voting
+        self.config.check_not_paused(PauseTarget::Voting)?;
+        // Check if proposal is open
+        self.proposal.is_open()?;
+        // Commits are only accepted during the voting window
+        self.proposal.check_expiry()?;
+        // Check the proposal uses sealed ballots
+        self.proposal.check_sealed_ballot()?;
+        // Make sure user has voting power
+        let voting_power = self.config.voting_power(
+            self.config.key(),
+            &self.stake_state,
+            self.voter_weight_record.as_deref(),
+            VoterWeightAction::CastVote,
+            Some(self.proposal.key()),
+            self.receipt_account.as_deref(),
+        )?;
+        require!(voting_power > 0, DaoError::InsufficientStake);
+        // The amount is sealed, so the commitment locks the whole stake until it is revealed or cleaned up
+        self.stake_state.add_account()?;
+        let staked = self.stake_state.amount;
+        self.stake_state.commit(staked)?;
+        self.member_state.add_open_vote()?;
+        self.proposal.add_commitment()?;
+        // Keep the receipts committed with from being voted with again
+        freeze_receipts(
+            &self.config,
+            self.token_program.as_ref(),
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+        )?;
+        // Initialize commitment
+        let slash_unrevealed = matches!(self.proposal.ballot, BallotMode::CommitReveal(_, true));
+        self.commitment.init(
+            self.config.key(),
+            self.owner.key(),
+            self.stake_state.index,
+            commitment,
+            slash_unrevealed,
+            bump,
+        )
+    }
+}
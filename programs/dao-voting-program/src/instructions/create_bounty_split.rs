@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DaoError,
+    state::{setup::DaoSetup, BountySplit, Proposal, ProposalType, SplitShare},
+};
+
+// Records the recipients of a SplitBounty proposal. Only the proposer can do it,
+// and only before anyone has voted.
+#[derive(Accounts)]
+pub struct CreateBountySplit<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.proposer == owner.key() @ DaoError::InvalidBountySplit
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"split", proposal.key().as_ref()],
+        bump,
+        space = BountySplit::LEN
+    )]
+    bounty_split: Account<'info, BountySplit>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateBountySplit<'info> {
+    pub fn create_bounty_split(&mut self, shares: Vec<SplitShare>, bump: u8) -> Result<()> {
+        self.proposal.is_open()?;
+        // Voters have to see the split, including those sealing their votes
+        self.proposal.check_no_votes()?;
+        let ProposalType::SplitBounty(total, mode) = self.proposal.proposal else {
+            return err!(DaoError::InvalidBountySplit);
+        };
+        self.bounty_split
+            .init(self.proposal.key(), shares, total, mode, bump)
+    }
+}
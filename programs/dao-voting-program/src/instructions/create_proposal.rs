@@ -2,9 +2,13 @@ use anchor_lang::{
     prelude::*,
     system_program::{transfer, Transfer},
 };
+use anchor_spl::{
+    metadata::MetadataAccount,
+    token::{transfer as transfer_spl, Mint, Token, TokenAccount, Transfer as TransferSpl},
+};
 
 use crate::{
-    constants::{PROPOSAL_CREATION_POINTS, PROPOSAL_CREATION_REPUTATION_INCREASE}, errors::DaoError, state::{setup::DaoSetup, MemberState, Proposal, ProposalType, StakeState}
+    constants::{PROPOSAL_CREATION_POINTS, PROPOSAL_CREATION_REPUTATION_INCREASE, ROLE_COUNCIL}, errors::DaoError, state::{check_collection_nft, setup::DaoSetup, BallotMode, MemberState, PauseTarget, Proposal, ProposalType, StakeState, VoterWeightAction}
 };
 
 #[derive(Accounts)]
@@ -12,12 +16,13 @@ use crate::{
 pub struct CreateProposal<'info> {
     #[account(mut)]
     owner: Signer<'info>,
+    // Not needed in NFT DAOs, where holding a collection NFT is enough to propose
     #[account(
         mut,
-        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref()],
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
         bump = stake_state.state_bump
     )]
-    stake_state: Account<'info, StakeState>,
+    stake_state: Option<Account<'info, StakeState>>,
     #[account(
         init,
         payer = owner,
@@ -26,10 +31,13 @@ pub struct CreateProposal<'info> {
         space = Proposal::LEN
     )]
     proposal: Account<'info, Proposal>,
+    // In NFT DAOs, holding a collection NFT makes the proposer a member
     #[account(
-        mut,
+        init_if_needed,
+        payer = owner,
+        space = MemberState::LEN,
         seeds=[b"member", config.key().as_ref(), owner.key().as_ref()],
-        bump = member_state.bump,
+        bump
     )]
     member_state: Account<'info, MemberState>,
     #[account(
@@ -38,10 +46,46 @@ pub struct CreateProposal<'info> {
     )]
     treasury: SystemAccount<'info>,
     #[account(
+        mut,
         seeds=[b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
     )]
     config: Account<'info, DaoSetup>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    // Required in NFT DAOs: a collection NFT held by the proposer and its metadata
+    nft_token_account: Option<Account<'info, TokenAccount>>,
+    nft_metadata: Option<Account<'info, MetadataAccount>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign things
+    auth: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>,
+    // Required when the DAO charges a proposal bond: the proposer's tokens and the escrow holding the bond
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    owner_ata: Option<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"bond", proposal.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = auth
+    )]
+    bond_escrow: Option<Account<'info, TokenAccount>>,
+    token_program: Program<'info, Token>,
     system_program: Program<'info, System>,
 }
 
@@ -54,22 +98,61 @@ impl<'info> CreateProposal<'info> {
         proposal: ProposalType,
         quorum: u64,
         expiry: u64,
+        ballot: BallotMode,
         bump: u8,
     ) -> Result<()> {
-        // Make sure user has staked
-        self.stake_state.check_stake()?;
+        // Check the guardian hasn't paused proposal creation
+        self.config.check_not_paused(PauseTarget::ProposalCreation)?;
+        // Make sure user has staked, or has weight from the voter-weight plugin or an NFT
+        let voting_power = match self.config.nft_collection {
+            Some(collection) => self.nft_voting_power(collection)?,
+            None => {
+                let stake_state = self
+                    .stake_state
+                    .as_ref()
+                    .ok_or(DaoError::InsufficientStake)?;
+                self.config.voting_power(
+                    self.config.key(),
+                    stake_state,
+                    self.voter_weight_record.as_deref(),
+                    VoterWeightAction::CreateProposal,
+                    None,
+                    self.receipt_account.as_deref(),
+                )?
+            }
+        };
+        require!(voting_power > 0, DaoError::InsufficientStake);
         // Check ID and add proposal
         self.config.add_proposal(id)?;
         // Check minimum quorum
         self.config.check_min_quorum(quorum)?;
         // Check max expiry
         self.config.check_max_expiry(expiry)?;
+        // Sealed ballots need a reveal window, bounded like the voting window
+        if let BallotMode::CommitReveal(reveal_period, _) = ballot {
+            require!(reveal_period > 0, DaoError::InvalidBallotMode);
+            self.config.check_max_expiry(reveal_period)?;
+        }
+        // Milestone bounties need at least one tranche
+        if let ProposalType::MilestoneBounty(_, _, _, tranches) = proposal {
+            require!(tranches > 0, DaoError::InvalidMilestoneBounty);
+        }
+        // The council role only means something in a DAO with a council to veto
+        if let ProposalType::SetRoles(_, roles) = proposal {
+            require!(
+                roles & ROLE_COUNCIL == 0 || self.config.has_council(),
+                DaoError::InvalidRoles
+            );
+        }
         // Initialize the proposal
         self.proposal.init(
             id, name, // A proposal name
             self.owner.key(),
             description, // 72 bytes (39 bytes + / + 32 byte ID)
-            proposal, quorum, expiry, bump,
+            proposal, quorum, expiry, ballot,
+            self.config.proposal_bond,
+            self.config.proposal_fee,
+            bump,
         )? ;
 
         // Update member state
@@ -77,11 +160,59 @@ impl<'info> CreateProposal<'info> {
             .add_proposal_points(PROPOSAL_CREATION_POINTS)?;
         self.member_state
             .update_reputation(PROPOSAL_CREATION_REPUTATION_INCREASE)?;
+        self.member_state.add_open_proposal()?;
 
         Ok(())
     }
 
+    // Initializes the member state of an NFT holder proposing before they've ever voted.
+    // Stakers' member states already exist, as staking creates them.
+    pub fn init_member(&mut self, bump: u8) -> Result<()> {
+        if self.member_state.join_date == 0 {
+            let nonce = self.config.next_membership_nonce()?;
+            self.member_state.init(self.owner.key(), nonce, bump)?;
+        }
+        Ok(())
+    }
+
+    // Checks the proposer holds an NFT of the DAO's collection
+    fn nft_voting_power(&self, collection: Pubkey) -> Result<u64> {
+        let (token_account, metadata) = self
+            .nft_token_account
+            .as_ref()
+            .zip(self.nft_metadata.as_ref())
+            .ok_or(DaoError::InvalidNft)?;
+        check_collection_nft(token_account, metadata, &self.owner.key(), &collection)?;
+        Ok(self.config.nft_vote_weight)
+    }
+
+    // Locks the proposer's bond in the proposal's escrow until the proposal is settled
+    pub fn lock_proposal_bond(&mut self) -> Result<()> {
+        if self.config.proposal_bond == 0 {
+            return Ok(());
+        }
+        let (owner_ata, bond_escrow) = self
+            .owner_ata
+            .as_ref()
+            .zip(self.bond_escrow.as_ref())
+            .ok_or(DaoError::InvalidBondAccount)?;
+
+        let accounts = TransferSpl {
+            from: owner_ata.to_account_info(),
+            to: bond_escrow.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+
+        transfer_spl(ctx, self.config.proposal_bond)
+    }
+
     pub fn pay_proposal_fee(&mut self) -> Result<()> {
+        // Refundable fees are held back from ragequits until the proposal is settled
+        let fee = self.config.proposal_fee;
+        self.config.escrow_fee(fee)?;
+
         let accounts = Transfer {
             from: self.owner.to_account_info(),
             to: self.treasury.to_account_info(),
@@ -89,6 +220,6 @@ impl<'info> CreateProposal<'info> {
 
         let ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
 
-        transfer(ctx, self.config.proposal_fee)
+        transfer(ctx, fee)
     }
 }
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DaoError,
+    state::{setup::DaoSetup, Proposal, ProposalType, StewardSet},
+};
+
+// Records the sub-treasury name and stewards of a CreateSubTreasury or SetStewards
+// proposal. Only the proposer can do it, and only before anyone has voted.
+#[derive(Accounts)]
+pub struct CreateStewardSet<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.proposer == owner.key() @ DaoError::InvalidStewardSet
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"stewards", proposal.key().as_ref()],
+        bump,
+        space = StewardSet::LEN
+    )]
+    steward_set: Account<'info, StewardSet>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateStewardSet<'info> {
+    pub fn create_steward_set(
+        &mut self,
+        name: String,
+        stewards: Vec<Pubkey>,
+        bump: u8,
+    ) -> Result<()> {
+        self.proposal.is_open()?;
+        // Voters have to see the stewards, including those sealing their votes
+        self.proposal.check_no_votes()?;
+        require!(
+            matches!(
+                self.proposal.proposal,
+                ProposalType::CreateSubTreasury | ProposalType::SetStewards(_)
+            ),
+            DaoError::InvalidStewardSet
+        );
+        self.steward_set
+            .init(self.proposal.key(), name, stewards, bump)
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer as transfer_spl, CloseAccount, Mint, Token, TokenAccount, Transfer as TransferSpl},
+};
+
+use crate::{
+    errors::DaoError,
+    state::{DaoSetup, VaultSpend},
+};
+
+// Creates the treasury's associated token account for any mint, so the DAO can hold
+// and pay out SPL tokens, along with the tracker enforcing the spending limits on it.
+// The token account may already exist, as anyone can create an associated token account.
+#[derive(Accounts)]
+pub struct CreateTreasuryVault<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    treasury_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"vault_spend", config.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = VaultSpend::LEN
+    )]
+    vault_spend: Account<'info, VaultSpend>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateTreasuryVault<'info> {
+    pub fn create_treasury_vault(&mut self, bumps: &BTreeMap<String, u8>) -> Result<()> {
+        self.vault_spend.init(
+            self.mint.key(),
+            *bumps.get("vault_spend").ok_or(DaoError::BumpError)?,
+        )
+    }
+}
+
+// Transfers tokens out of a token account owned by the treasury, signing with the treasury PDA
+pub fn transfer_from_treasury_vault<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    config: Pubkey,
+    treasury_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let accounts = TransferSpl {
+        from,
+        to,
+        authority: treasury,
+    };
+    let seeds = &[
+        &b"treasury"[..],
+        &config.to_bytes()[..],
+        &[treasury_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let ctx = CpiContext::new_with_signer(token_program, accounts, signer_seeds);
+    transfer_spl(ctx, amount)
+}
+
+// Closes an empty token account owned by the treasury, returning its rent to the treasury
+pub fn close_treasury_token_account<'info>(
+    token_program: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    config: Pubkey,
+    treasury_bump: u8,
+) -> Result<()> {
+    let accounts = CloseAccount {
+        account,
+        destination: treasury.clone(),
+        authority: treasury,
+    };
+    let seeds = &[
+        &b"treasury"[..],
+        &config.to_bytes()[..],
+        &[treasury_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let ctx = CpiContext::new_with_signer(token_program, accounts, signer_seeds);
+    close_account(ctx)
+}
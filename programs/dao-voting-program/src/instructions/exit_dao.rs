@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    instructions::{burn_receipts, close_stake_vault, pay_rewards, withdraw_from_vault},
+    state::{setup::DaoSetup, MemberState, RewardPool, StakeState},
+};
+
+// Leaves the DAO: unstakes everything from the member's last stake position, pays out
+// pending rewards and closes the vault, stake state and member state, refunding their rent
+#[derive(Accounts)]
+pub struct ExitDao<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    owner_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.vault_bump,
+        token::mint = mint,
+        token::authority = stake_auth
+    )]
+    stake_ata: Account<'info, TokenAccount>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref(), owner.key().as_ref()],
+        bump = stake_state.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign things
+    stake_auth: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        close = owner,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Account<'info, RewardPool>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    #[account(
+        mut,
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    /// Holds the staker's receipts with liquid staking, validated against the receipt mint
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt thaws and freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ExitDao<'info> {
+    pub fn exit_dao(&mut self) -> Result<()> {
+        // Every other position has to be closed, and this one can't back any open vote.
+        // Proposals the member created need their member state to be executed or cleaned up.
+        self.member_state.check_last_stake_position()?;
+        self.member_state.check_no_open_proposals()?;
+        self.stake_state.check_accounts()?;
+
+        // Unstake everything, settling rewards earned at the previous amount first
+        let amount = self.stake_state.amount;
+        let reward_per_token = self.reward_pool.reward_per_token;
+        self.stake_state.settle_rewards(reward_per_token)?;
+        self.stake_state.unstake(amount)?;
+        self.reward_pool.unstake(amount)?;
+
+        // Pay out any rewards still owed to the position
+        let rewards = self.stake_state.pending_rewards;
+        if rewards > 0 {
+            self.stake_state.take_rewards()?;
+            self.reward_pool.claim(rewards)?;
+            pay_rewards(
+                &self.reward_pool.to_account_info(),
+                &self.owner.to_account_info(),
+                rewards,
+            )?;
+        }
+
+        if amount > 0 {
+            burn_receipts(
+                &self.config,
+                &self.token_program,
+                self.receipt_mint.as_ref(),
+                self.receipt_account.as_ref(),
+                self.dao_auth.as_ref(),
+                &self.owner,
+                amount,
+            )?;
+            withdraw_from_vault(
+                &self.token_program,
+                &self.stake_ata,
+                self.owner_ata.to_account_info(),
+                self.stake_auth.to_account_info(),
+                self.config.key(),
+                self.owner.key(),
+                self.stake_state.auth_bump,
+                amount,
+            )?;
+        }
+
+        close_stake_vault(
+            &self.token_program,
+            &self.stake_ata,
+            self.owner.to_account_info(),
+            self.stake_auth.to_account_info(),
+            self.config.key(),
+            self.owner.key(),
+            self.stake_state.auth_bump,
+        )
+    }
+}
@@ -1,11 +1,26 @@
+use std::collections::BTreeMap;
+
 use crate::{
     constants::*,
     errors::DaoError,
-    state::{setup::DaoSetup, MemberState, Proposal, ProposalType},
+    instructions::{
+        close_treasury_token_account, release_tranche, return_unspent_funds,
+        transfer_from_treasury_vault,
+    },
+    state::{
+        setup::DaoSetup, BountySplit, MemberState, MilestoneBounty, PauseTarget, PaymentStream,
+        Proposal, ProposalType, RewardPool, SplitMode, StreamUnit, StewardSet, SubTreasury, VaultSpend,
+    },
+    utils::create_pda_account,
 };
 use anchor_lang::{
     prelude::*,
     system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token::{
+    close_account, transfer as transfer_spl, CloseAccount, Mint, Token, TokenAccount,
+    Transfer as TransferSpl,
 };
 
 // This struct defines the accounts required for the CleanupProposal instruction
@@ -33,44 +48,246 @@ pub struct FinalizeProposal<'info> {
     )]
     proposer_state: Account<'info, MemberState>, //The state of the proposer account
 
+    /// CHECK: This account is not dangerous because it must be the proposer recorded in the proposal
+    #[account(
+        mut,
+        address = proposal.proposer
+    )]
+    proposer: Option<UncheckedAccount<'info>>, // Receives a refunded proposal fee
+
     #[account(
+        mut,
         seeds=[b"treasury", config.key().as_ref()],
         bump = config.treasury_bump
     )]
     treasury: SystemAccount<'info>, // The DAO's treasury account
 
     #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Option<Account<'info, RewardPool>>, // The stakers' reward pool, required for FundRewards proposals
+
+    #[account(
+        mut,
         seeds=[b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
     )]
-    config: Account<'info, DaoSetup>, // The DAO's configuration account
+    config: Account<'info, DaoSetup>, // The DAO's configuration account, tracking treasury spending
+
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign things
+    auth: UncheckedAccount<'info>, // The DAO's authority, owner of the bond escrow
+
+    #[account(
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>, // The DAO's governance token mint
+
+    #[account(
+        mut,
+        seeds = [b"bond", proposal.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = auth
+    )]
+    bond_escrow: Option<Account<'info, TokenAccount>>, // The proposer's locked bond, required when the proposal has one
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = proposal.proposer
+    )]
+    proposer_ata: Option<Account<'info, TokenAccount>>, // Receives a returned bond
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    treasury_ata: Option<Account<'info, TokenAccount>>, // Receives a slashed bond
+
+    #[account(
+        mut,
+        associated_token::mint = token_bounty_mint,
+        associated_token::authority = treasury
+    )]
+    treasury_vault: Option<Account<'info, TokenAccount>>, // The treasury's vault paying a token bounty
+
+    token_bounty_mint: Option<Account<'info, Mint>>, // The mint of a token bounty or token stream
+
+    #[account(
+        mut,
+        seeds = [b"vault_spend", config.key().as_ref(), vault_spend.mint.as_ref()],
+        bump = vault_spend.bump
+    )]
+    vault_spend: Option<Box<Account<'info, VaultSpend>>>, // Tracks spending from the treasury vault, required with it
+
+    #[account(mut)]
+    payee_token_account: Option<Account<'info, TokenAccount>>, // Receives a token bounty, validated against the proposal
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"milestone", config.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        space = MilestoneBounty::LEN
+    )]
+    new_milestone_bounty: Option<Account<'info, MilestoneBounty>>, // Escrow created by a MilestoneBounty proposal
+
+    #[account(
+        mut,
+        seeds = [b"milestone", config.key().as_ref(), milestone_bounty.proposal.as_ref()],
+        bump = milestone_bounty.bump
+    )]
+    milestone_bounty: Option<Account<'info, MilestoneBounty>>, // Escrow targeted by a ReleaseMilestone or AbandonMilestone proposal
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"stream", config.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        space = PaymentStream::LEN
+    )]
+    new_payment_stream: Option<Box<Account<'info, PaymentStream>>>, // Stream created by a PaymentStream proposal
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"stream_vault", config.key().as_ref(), proposal.key().as_ref()],
+        bump,
+        token::mint = token_bounty_mint,
+        token::authority = treasury
+    )]
+    new_stream_vault: Option<Box<Account<'info, TokenAccount>>>, // Escrow of a new token stream
+
+    #[account(
+        mut,
+        seeds = [b"stream", config.key().as_ref(), payment_stream.proposal.as_ref()],
+        bump = payment_stream.bump
+    )]
+    payment_stream: Option<Box<Account<'info, PaymentStream>>>, // Stream targeted by a CancelStream proposal
+
+    #[account(mut)]
+    stream_vault: Option<Box<Account<'info, TokenAccount>>>, // Escrow of a cancelled token stream, validated against the stream
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"split", proposal.key().as_ref()],
+        bump = bounty_split.bump
+    )]
+    bounty_split: Option<Box<Account<'info, BountySplit>>>, // Recipients of a SplitBounty proposal
+
+    #[account(
+        mut,
+        seeds = [b"sub_treasury", config.key().as_ref(), sub_treasury.name.as_bytes()],
+        bump = sub_treasury.bump
+    )]
+    sub_treasury: Option<Box<Account<'info, SubTreasury>>>, // Sub-treasury funded by a FundSubTreasury proposal or given new stewards by a SetStewards proposal
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"stewards", proposal.key().as_ref()],
+        bump = steward_set.bump
+    )]
+    steward_set: Option<Box<Account<'info, StewardSet>>>, // Name and stewards of a CreateSubTreasury or SetStewards proposal
+
+    /// CHECK: This account is not dangerous because it's checked against the address derived from the steward set's name and created empty
+    #[account(mut)]
+    new_sub_treasury: Option<UncheckedAccount<'info>>, // Sub-treasury created by a CreateSubTreasury proposal
+
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), role_member_state.address.as_ref()],
+        bump = role_member_state.bump
+    )]
+    role_member_state: Option<Box<Account<'info, MemberState>>>, // Member given roles by a SetRoles proposal
 
+    token_program: Program<'info, Token>, // Required for bond transfers
     system_program: Program<'info, System>, // Required for SOL transfers
 }
 
 impl<'info> FinalizeProposal<'info> {
-    // This function cleans up a failed proposal
+    // This function cleans up a failed or vetoed proposal
     pub fn cleanup_proposal(&mut self) -> Result<()> {
         let _ = self.proposal.try_finalize(); // Attempt to finalize the proposal
-        self.proposal.is_failed()?; // Ensure the proposal has failed
-        Ok(())
+        self.proposal.is_rejected()?; // Ensure the proposal has failed or was vetoed
+        self.proposer_state.remove_open_proposal()?; // The proposer no longer has it open
+        self.settle_bond()?; // Return or slash the proposer's bond
+        self.refund_fee() // Refund the proposal fee, if the policy allows
     }
 
     // This function executes a successful proposal
-    pub fn execute_proposal(&mut self) -> Result<()> {
+    pub fn execute_proposal(
+        &mut self,
+        bumps: &BTreeMap<String, u8>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
         self.proposal.try_finalize()?; // Attempt to finalize the proposal
         self.proposal.is_succeeded()?; // Ensure the proposal has succeeded
+        if self.config.has_council() {
+            self.proposal.check_veto_window_closed(self.config.veto_window)?; // Give the council a chance to veto
+        }
+        if self.proposal.proposal.pays_out() {
+            self.config.check_not_paused(PauseTarget::Payouts)?; // Ensure the guardian hasn't paused payouts
+        }
+        self.settle_bond()?; // Return the proposer's bond
+        self.refund_fee()?; // Refund the proposal fee, if the policy allows
 
         // Add reward points and increase reputation for the proposer
         self.proposer_state
             .add_proposal_success_points(PROPOSAL_SUCCESS_POINTS)?;
         self.proposer_state
             .update_reputation(PROPOSAL_SUCCESS_REPUTATION_INCREASE)?;
+        self.proposer_state.remove_open_proposal()?;
+
+        // Enforce the treasury spending limits on any SOL leaving the treasury
+        let spend = self.proposal.proposal.treasury_spend()?;
+        if spend > 0 {
+            let yes_share_bps = self.proposal.yes_share_bps()?;
+            let treasury_balance = self.treasury.lamports();
+            self.config
+                .record_spend(spend, treasury_balance, yes_share_bps)?;
+        }
+        // And on tokens leaving a treasury vault, as shares of the vault's balance
+        if let Some((mint, amount)) = self.proposal.proposal.treasury_token_spend()? {
+            self.record_token_spend(mint, amount)?;
+        }
 
         match self.proposal.proposal {
             ProposalType::Bounty(payee, payout) => self.payout_bounty(payee, payout),
             ProposalType::Executable => self.execute_tx(),
             ProposalType::Vote => self.finalize_vote(),
+            ProposalType::FundRewards(amount) => self.fund_rewards(amount),
+            ProposalType::TokenBounty(mint, payee, amount) => {
+                self.payout_token_bounty(mint, payee, amount)
+            }
+            ProposalType::MilestoneBounty(payee, reviewer, total, tranches) => {
+                self.fund_milestone_bounty(payee, reviewer, total, tranches, bumps)
+            }
+            ProposalType::ReleaseMilestone(bounty) => self.release_milestone(bounty),
+            ProposalType::AbandonMilestone(bounty) => self.abandon_milestone(bounty),
+            ProposalType::PaymentStream(recipient, mint, rate, start, end, unit) => {
+                self.fund_payment_stream(recipient, mint, rate, start, end, unit, bumps)
+            }
+            ProposalType::CancelStream(stream) => self.cancel_payment_stream(stream),
+            ProposalType::SplitBounty(total, mode) => {
+                self.payout_split_bounty(total, mode, remaining_accounts)
+            }
+            ProposalType::FundSubTreasury(sub_treasury, budget, period) => {
+                self.fund_sub_treasury(sub_treasury, budget, period)
+            }
+            ProposalType::SetGuardian(guardian) => self.set_guardian(guardian),
+            ProposalType::SetRoles(member, roles) => self.set_roles(member, roles),
+            ProposalType::CreateSubTreasury => self.create_sub_treasury(),
+            ProposalType::SetStewards(sub_treasury) => self.set_stewards(sub_treasury),
         }
     }
 
@@ -88,22 +305,412 @@ impl<'info> FinalizeProposal<'info> {
     // This function pays out a bounty to the specified payee
     pub fn payout_bounty(&self, payee: Pubkey, payout: u64) -> Result<()> {
         require_keys_eq!(self.payee.key(), payee); // Ensure the payee account matches the proposal
-        let accounts = Transfer {
-            from: self.treasury.to_account_info(),
-            to: self.payee.to_account_info(),
+        self.transfer_from_treasury(self.payee.to_account_info(), payout) // Transfer the bounty from the treasury to the payee
+    }
+
+    // This function pays out a token bounty from the treasury's vault for the mint, signing with the treasury PDA
+    pub fn payout_token_bounty(&self, mint: Pubkey, payee: Pubkey, amount: u64) -> Result<()> {
+        let (treasury_vault, payee_token_account) = self
+            .treasury_vault
+            .as_ref()
+            .zip(self.payee_token_account.as_ref())
+            .ok_or(DaoError::InvalidTokenBountyAccount)?;
+        require_keys_eq!(treasury_vault.mint, mint, DaoError::InvalidTokenBountyAccount);
+        require_keys_eq!(payee_token_account.mint, mint, DaoError::InvalidTokenBountyAccount);
+        require_keys_eq!(payee_token_account.owner, payee, DaoError::InvalidTokenBountyAccount);
+
+        transfer_from_treasury_vault(
+            self.token_program.to_account_info(),
+            treasury_vault.to_account_info(),
+            payee_token_account.to_account_info(),
+            self.treasury.to_account_info(),
+            self.config.key(),
+            self.config.treasury_bump,
+            amount,
+        )
+    }
+
+    // This function records tokens leaving the treasury's vault for the mint against the spending limits
+    fn record_token_spend(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        let yes_share_bps = self.proposal.yes_share_bps()?;
+        let (treasury_vault, vault_spend) = self
+            .treasury_vault
+            .as_ref()
+            .zip(self.vault_spend.as_mut())
+            .ok_or(DaoError::InvalidTokenBountyAccount)?;
+        require_keys_eq!(treasury_vault.mint, mint, DaoError::InvalidTokenBountyAccount);
+        require_keys_eq!(vault_spend.mint, mint, DaoError::InvalidTokenBountyAccount);
+        vault_spend.record_spend(&self.config, amount, treasury_vault.amount, yes_share_bps)
+    }
+
+    // This function escrows a milestone bounty's SOL, to be released tranche by tranche
+    pub fn fund_milestone_bounty(
+        &mut self,
+        payee: Pubkey,
+        reviewer: Pubkey,
+        total: u64,
+        tranches: u8,
+        bumps: &BTreeMap<String, u8>,
+    ) -> Result<()> {
+        let proposal = self.proposal.key();
+        let bounty = self
+            .new_milestone_bounty
+            .as_mut()
+            .ok_or(DaoError::InvalidMilestoneBounty)?;
+        bounty.init(
+            proposal,
+            payee,
+            reviewer,
+            total,
+            tranches,
+            *bumps.get("new_milestone_bounty").ok_or(DaoError::BumpError)?,
+        )?;
+        let bounty_info = bounty.to_account_info();
+        self.transfer_from_treasury(bounty_info, total)
+    }
+
+    // This function releases the next tranche of a milestone bounty after a confirm vote
+    pub fn release_milestone(&mut self, bounty: Pubkey) -> Result<()> {
+        let milestone_bounty = self
+            .milestone_bounty
+            .as_mut()
+            .ok_or(DaoError::InvalidMilestoneBounty)?;
+        require_keys_eq!(milestone_bounty.key(), bounty, DaoError::InvalidMilestoneBounty);
+        require_keys_eq!(self.payee.key(), milestone_bounty.payee); // Ensure the payee account matches the bounty
+        release_tranche(
+            milestone_bounty,
+            &self.payee.to_account_info(),
+            &self.treasury.to_account_info(),
+        )
+    }
+
+    // This function closes an abandoned milestone bounty, returning unreleased SOL to the treasury
+    pub fn abandon_milestone(&mut self, bounty: Pubkey) -> Result<()> {
+        let milestone_bounty = self
+            .milestone_bounty
+            .as_ref()
+            .ok_or(DaoError::InvalidMilestoneBounty)?;
+        require_keys_eq!(milestone_bounty.key(), bounty, DaoError::InvalidMilestoneBounty);
+        milestone_bounty.close(self.treasury.to_account_info())
+    }
+
+    // This function creates a payment stream and escrows its total from the treasury
+    pub fn fund_payment_stream(
+        &mut self,
+        recipient: Pubkey,
+        mint: Option<Pubkey>,
+        rate: u64,
+        start: u64,
+        end: u64,
+        unit: StreamUnit,
+        bumps: &BTreeMap<String, u8>,
+    ) -> Result<()> {
+        let proposal = self.proposal.key();
+        let vault_bump = match mint {
+            Some(_) => *bumps.get("new_stream_vault").ok_or(DaoError::InvalidStreamAccount)?,
+            None => 0,
         };
+        let stream = self
+            .new_payment_stream
+            .as_mut()
+            .ok_or(DaoError::InvalidStreamAccount)?;
+        stream.init(
+            proposal,
+            recipient,
+            mint,
+            unit,
+            rate,
+            start,
+            end,
+            *bumps.get("new_payment_stream").ok_or(DaoError::BumpError)?,
+            vault_bump,
+        )?;
+        let total = stream.total()?;
+        let stream_info = stream.to_account_info();
+
+        match mint {
+            None => self.transfer_from_treasury(stream_info, total),
+            Some(mint) => {
+                let (treasury_vault, stream_vault) = self
+                    .treasury_vault
+                    .as_ref()
+                    .zip(self.new_stream_vault.as_ref())
+                    .ok_or(DaoError::InvalidStreamAccount)?;
+                require_keys_eq!(stream_vault.mint, mint, DaoError::InvalidStreamAccount);
+                transfer_from_treasury_vault(
+                    self.token_program.to_account_info(),
+                    treasury_vault.to_account_info(),
+                    stream_vault.to_account_info(),
+                    self.treasury.to_account_info(),
+                    self.config.key(),
+                    self.config.treasury_bump,
+                    total,
+                )
+            }
+        }
+    }
+
+    // This function stops a payment stream and returns its unvested remainder to the treasury
+    pub fn cancel_payment_stream(&mut self, stream: Pubkey) -> Result<()> {
+        let dao = self.config.key();
+        let payment_stream = self
+            .payment_stream
+            .as_mut()
+            .ok_or(DaoError::InvalidStreamAccount)?;
+        require_keys_eq!(payment_stream.key(), stream, DaoError::InvalidStreamAccount);
+        let remainder = payment_stream.cancel()?;
+
+        match payment_stream.mint {
+            // The stream is owned by this program, so lamports can be moved directly
+            None => {
+                let stream_info = payment_stream.to_account_info();
+                let treasury_info = self.treasury.to_account_info();
+                **stream_info.try_borrow_mut_lamports()? = stream_info
+                    .lamports()
+                    .checked_sub(remainder)
+                    .ok_or(DaoError::Underflow)?;
+                **treasury_info.try_borrow_mut_lamports()? = treasury_info
+                    .lamports()
+                    .checked_add(remainder)
+                    .ok_or(DaoError::Overflow)?;
+            }
+            Some(_) => {
+                let (stream_vault, treasury_vault) = self
+                    .stream_vault
+                    .as_ref()
+                    .zip(self.treasury_vault.as_ref())
+                    .ok_or(DaoError::InvalidStreamAccount)?;
+                payment_stream.check_vault(dao, stream_vault)?;
+                transfer_from_treasury_vault(
+                    self.token_program.to_account_info(),
+                    stream_vault.to_account_info(),
+                    treasury_vault.to_account_info(),
+                    self.treasury.to_account_info(),
+                    dao,
+                    self.config.treasury_bump,
+                    remainder,
+                )?;
+                if payment_stream.is_finished()? {
+                    close_treasury_token_account(
+                        self.token_program.to_account_info(),
+                        stream_vault.to_account_info(),
+                        self.treasury.to_account_info(),
+                        dao,
+                        self.config.treasury_bump,
+                    )?;
+                }
+            }
+        }
+
+        // Nothing is left for the recipient to withdraw
+        if payment_stream.is_finished()? {
+            payment_stream.close(self.treasury.to_account_info())?;
+        }
+        Ok(())
+    }
+
+    // This function pays a split bounty to the recipients stored in its BountySplit,
+    // passed as remaining accounts in the same order
+    pub fn payout_split_bounty(
+        &self,
+        total: u64,
+        mode: SplitMode,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let bounty_split = self
+            .bounty_split
+            .as_ref()
+            .ok_or(DaoError::InvalidBountySplit)?;
+        let payouts = bounty_split.payouts(total, mode)?;
+        require!(
+            remaining_accounts.len() == payouts.len(),
+            DaoError::InvalidBountySplit
+        );
+        for ((recipient, amount), recipient_info) in payouts.into_iter().zip(remaining_accounts) {
+            require_keys_eq!(recipient_info.key(), recipient, DaoError::InvalidBountySplit);
+            self.transfer_from_treasury(recipient_info.clone(), amount)?;
+        }
+        Ok(())
+    }
+
+    // This function returns a sub-treasury's unspent funds and starts a new budget period
+    pub fn fund_sub_treasury(&mut self, sub_treasury: Pubkey, budget: u64, period: u64) -> Result<()> {
+        let treasury_info = self.treasury.to_account_info();
+        let account = self
+            .sub_treasury
+            .as_mut()
+            .ok_or(DaoError::InvalidSubTreasury)?;
+        require_keys_eq!(account.key(), sub_treasury, DaoError::InvalidSubTreasury);
+        return_unspent_funds(account, &treasury_info)?;
+        account.start_period(budget, period)?;
+        let account_info = account.to_account_info();
+        self.transfer_from_treasury(account_info, budget)
+    }
+
+    // This function creates the sub-treasury recorded in the steward set, unfunded
+    pub fn create_sub_treasury(&mut self) -> Result<()> {
+        let steward_set = self
+            .steward_set
+            .as_ref()
+            .ok_or(DaoError::InvalidStewardSet)?;
+        let target = self
+            .new_sub_treasury
+            .as_ref()
+            .ok_or(DaoError::InvalidSubTreasury)?
+            .to_account_info();
+        let config = self.config.key();
+        let (address, bump) = Pubkey::find_program_address(
+            &[b"sub_treasury", config.as_ref(), steward_set.name.as_bytes()],
+            &crate::ID,
+        );
+        require_keys_eq!(target.key(), address, DaoError::InvalidSubTreasury);
+
+        let seeds = &[
+            &b"sub_treasury"[..],
+            &config.to_bytes()[..],
+            steward_set.name.as_bytes(),
+            &[bump],
+        ];
+        create_pda_account(
+            &self.initializer.to_account_info(),
+            &target,
+            SubTreasury::LEN,
+            &self.system_program.to_account_info(),
+            &[&seeds[..]],
+        )?;
+        target.try_borrow_mut_data()?[..8].copy_from_slice(&SubTreasury::DISCRIMINATOR);
+        let mut sub_treasury: Account<'info, SubTreasury> = Account::try_from(&target)?;
+        sub_treasury.init(steward_set.name.clone(), steward_set.stewards.clone(), bump)?;
+        // Persist the account, as Anchor only does this for the accounts struct
+        sub_treasury.exit(&crate::ID)
+    }
+
+    // This function replaces a sub-treasury's stewards with those recorded in the steward set
+    pub fn set_stewards(&mut self, sub_treasury: Pubkey) -> Result<()> {
+        let steward_set = self
+            .steward_set
+            .as_ref()
+            .ok_or(DaoError::InvalidStewardSet)?;
+        let account = self
+            .sub_treasury
+            .as_mut()
+            .ok_or(DaoError::InvalidSubTreasury)?;
+        require_keys_eq!(account.key(), sub_treasury, DaoError::InvalidSubTreasury);
+        require!(account.name == steward_set.name, DaoError::InvalidStewardSet);
+        account.set_stewards(steward_set.stewards.clone())
+    }
+
+    // This function replaces the DAO's guardian
+    pub fn set_guardian(&mut self, guardian: Option<Pubkey>) -> Result<()> {
+        self.config.guardian = guardian;
+        Ok(())
+    }
+
+    // This function replaces a member's roles
+    pub fn set_roles(&mut self, member: Pubkey, roles: u8) -> Result<()> {
+        let member_state = self
+            .role_member_state
+            .as_mut()
+            .ok_or(DaoError::InvalidRoles)?;
+        require_keys_eq!(member_state.address, member, DaoError::InvalidRoles);
+        member_state.set_roles(roles)
+    }
+
+    // This function moves SOL from the treasury to the reward pool and distributes it to stakers
+    pub fn fund_rewards(&mut self, amount: u64) -> Result<()> {
+        let reward_pool = self
+            .reward_pool
+            .as_ref()
+            .ok_or(DaoError::MissingRewardPool)?
+            .to_account_info();
+        self.transfer_from_treasury(reward_pool, amount)?;
+        self.reward_pool
+            .as_mut()
+            .ok_or(DaoError::MissingRewardPool)?
+            .distribute(amount)
+    }
+
+    // This function returns the proposer's bond, or slashes it to the treasury, and closes the escrow
+    fn settle_bond(&self) -> Result<()> {
+        if self.proposal.bond == 0 {
+            return Ok(());
+        }
+        let bond_escrow = self
+            .bond_escrow
+            .as_ref()
+            .ok_or(DaoError::InvalidBondAccount)?;
+        let destination = if self.proposal.is_bond_slashed(self.config.bond_slash_bps)? {
+            self.treasury_ata.as_ref()
+        } else {
+            self.proposer_ata.as_ref()
+        }
+        .ok_or(DaoError::InvalidBondAccount)?;
+
         let seeds = &[
             &b"auth"[..],
             &self.config.key().to_bytes()[..],
             &[self.config.auth_bump],
         ];
         let signer_seeds = &[&seeds[..]];
+
+        let accounts = TransferSpl {
+            from: bond_escrow.to_account_info(),
+            to: destination.to_account_info(),
+            authority: self.auth.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        transfer_spl(ctx, self.proposal.bond)?;
+
+        // The escrow's rent follows the proposal's to the treasury
+        let accounts = CloseAccount {
+            account: bond_escrow.to_account_info(),
+            destination: self.treasury.to_account_info(),
+            authority: self.auth.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        close_account(ctx)
+    }
+
+    // This function refunds the part of the proposal fee the DAO's policy allows
+    fn refund_fee(&mut self) -> Result<()> {
+        self.config.release_fee(self.proposal.fee)?;
+        let refund = self.proposal.fee_refund(self.config.fee_refund_policy)?;
+        if refund == 0 {
+            return Ok(());
+        }
+        let proposer = self
+            .proposer
+            .as_ref()
+            .ok_or(DaoError::MissingProposer)?;
+        self.transfer_from_treasury(proposer.to_account_info(), refund)
+    }
+
+    // This function transfers SOL out of the treasury, signing with the treasury PDA
+    fn transfer_from_treasury(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let accounts = Transfer {
+            from: self.treasury.to_account_info(),
+            to,
+        };
+        let seeds = &[
+            &b"treasury"[..],
+            &self.config.key().to_bytes()[..],
+            &[self.config.treasury_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
         let ctx = CpiContext::new_with_signer(
             self.system_program.to_account_info(),
             accounts,
             signer_seeds,
         );
-        transfer(ctx, payout) // Transfer the bounty from the treasury to the payee
+        transfer(ctx, amount)
     }
 
     // This function is a placeholder for executing a transaction proposal
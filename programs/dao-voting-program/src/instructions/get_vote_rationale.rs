@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::{setup::DaoSetup, Proposal, VoteRationaleView, VoteState};
+
+#[derive(Accounts)]
+pub struct GetVoteRationale<'info> {
+    /// CHECK: This account is not written to, only used as a key for PDA derivation.
+    pub voter: AccountInfo<'info>,
+    #[account(
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        seeds=[b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump = vote.bump
+    )]
+    pub vote: Account<'info, VoteState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    pub config: Account<'info, DaoSetup>,
+}
+
+impl<'info> GetVoteRationale<'info> {
+    pub fn get_vote_rationale(&self) -> Result<VoteRationaleView> {
+        Ok(VoteRationaleView {
+            voter: self.vote.owner,
+            proposal_id: self.proposal.id,
+            amount: self.vote.amount,
+            weight: self.vote.weight,
+            vote_type: self.vote.vote_type,
+            rationale: self.vote.rationale.clone(),
+        })
+    }
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{setup::DaoSetup, PauseTarget};
+
+// Lets the guardian pause part of the DAO for a limited time, or lift a pause early.
+// The guardian can only be changed by a SetGuardian proposal.
+#[derive(Accounts)]
+pub struct Guardian<'info> {
+    guardian: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> Guardian<'info> {
+    pub fn pause(&mut self, target: PauseTarget, duration: u64) -> Result<()> {
+        self.config.check_guardian(self.guardian.key())?;
+        self.config.pause(target, duration)
+    }
+
+    pub fn unpause(&mut self, target: PauseTarget) -> Result<()> {
+        self.config.check_guardian(self.guardian.key())?;
+        self.config.unpause(target)
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 
-use crate::{errors::DaoError, state::DaoSetup};
+use crate::{errors::DaoError, state::{DaoSetup, DaoSettings, RewardPool}};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -30,6 +30,17 @@ pub struct Initialize<'info> {
         mint::decimals = 0
     )]
     mint: Account<'info, Mint>,
+    /// Only created with liquid staking. The authority freezes receipts backing open votes.
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"receipt", config.key().as_ref()],
+        bump,
+        mint::authority = auth,
+        mint::freeze_authority = auth,
+        mint::decimals = 0
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
     #[account(
         init,
         payer = initializer,
@@ -38,6 +49,14 @@ pub struct Initialize<'info> {
         space = DaoSetup::LEN
     )]
     config: Account<'info, DaoSetup>,
+    #[account(
+        init,
+        payer = initializer,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump,
+        space = RewardPool::LEN
+    )]
+    reward_pool: Account<'info, RewardPool>,
     token_program: Program<'info, Token>,
     system_program: Program<'info, System>
 }
@@ -47,37 +66,39 @@ impl<'info> Initialize<'info> {
         &mut self, 
         seed: u64,
         bumps: &BTreeMap<String, u8>,
-        issue_price: u64,
-        issue_amount: u64,
-        proposal_fee: u64,
-        max_supply: u64,
-        min_quorum: u64,
-        max_expiry: u64,
+        settings: DaoSettings,
     ) -> Result<()> {
+        // The receipt mint exists exactly when staking is liquid
+        require!(
+            self.receipt_mint.is_some() == settings.liquid_staking,
+            DaoError::InvalidReceiptAccount
+        );
+
         let (
             auth_bump,
             config_bump,
             mint_bump,
-            treasury_bump
+            treasury_bump,
+            receipt_mint_bump
         ) = (
             *bumps.get("auth").ok_or(DaoError::BumpError)?,
             *bumps.get("config").ok_or(DaoError::BumpError)?,
             *bumps.get("mint").ok_or(DaoError::BumpError)?,
             *bumps.get("treasury").ok_or(DaoError::BumpError)?,
+            bumps.get("receipt_mint").copied().unwrap_or_default(),
         );
 
+        self.reward_pool
+            .init(*bumps.get("reward_pool").ok_or(DaoError::BumpError)?)?;
+
         self.config.init(
             seed,
-            issue_price,
-            issue_amount,
-            proposal_fee,
-            max_supply,
-            min_quorum,
-            max_expiry,
+            settings,
             auth_bump,
             config_bump,
             mint_bump,
-            treasury_bump
+            treasury_bump,
+            receipt_mint_bump
         )
     }
 }
\ No newline at end of file
@@ -1,7 +1,6 @@
 pub mod initialize_dao;
 pub mod vote;
 pub mod issue_governance_tokens;
-pub mod initialize_stake;
 pub mod create_proposal;
 pub mod stake;
 pub mod get_proposal_results;
@@ -9,15 +8,64 @@ mod close_stake_account;
 mod finalize_proposal;
 mod remove_or_cleanup_vote;
 mod get_member_state;
+mod vote_by_signature;
+mod commit_vote;
+mod reveal_vote;
+mod cleanup_commit;
+mod batch_vote;
+mod get_vote_rationale;
+mod nft_vote;
+mod cleanup_nft_vote;
+mod claim_rewards;
+mod unstake;
+mod exit_dao;
+mod staking_receipt;
+mod thaw_receipts;
+mod stake_for;
+mod reject_stake;
+mod create_treasury_vault;
+mod review_milestone;
+mod withdraw_stream;
+mod create_bounty_split;
+mod sub_treasury;
+mod create_steward_set;
+mod ragequit;
+mod guardian;
+mod veto_proposal;
+mod set_nft_weight;
 
 pub use initialize_dao::*;
 pub use vote::*;
 pub use issue_governance_tokens::*;
-pub use initialize_stake::*;
 pub use create_proposal::*;
 pub use stake::*;
 pub use get_proposal_results::*;
 pub use close_stake_account::*;
 pub use finalize_proposal::*;
 pub use remove_or_cleanup_vote::*;
-pub use get_member_state::*;
\ No newline at end of file
+pub use get_member_state::*;
+pub use vote_by_signature::*;
+pub use commit_vote::*;
+pub use reveal_vote::*;
+pub use cleanup_commit::*;
+pub use batch_vote::*;
+pub use get_vote_rationale::*;
+pub use nft_vote::*;
+pub use cleanup_nft_vote::*;
+pub use claim_rewards::*;
+pub use unstake::*;
+pub use exit_dao::*;
+pub use staking_receipt::*;
+pub use thaw_receipts::*;
+pub use stake_for::*;
+pub use reject_stake::*;
+pub use create_treasury_vault::*;
+pub use review_milestone::*;
+pub use withdraw_stream::*;
+pub use create_bounty_split::*;
+pub use sub_treasury::*;
+pub use create_steward_set::*;
+pub use ragequit::*;
+pub use guardian::*;
+pub use veto_proposal::*;
+pub use set_nft_weight::*;
\ No newline at end of file
@@ -0,0 +1,177 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::{metadata::MetadataAccount, token::TokenAccount};
+use crate::{
+    constants::*,
+    errors::DaoError,
+    state::{check_collection_nft, setup::DaoSetup, MemberState, NftVoteRecord, NftWeight, PauseTarget, Proposal, VoteType},
+    utils::create_pda_account,
+};
+
+// Votes with every NFT passed as a [token account, metadata, vote record] triple of
+// `remaining_accounts`. Each NFT adds the DAO's per-NFT weight to the tally once. When the
+// DAO has an NFT weight authority, each NFT also passes its weight account, as
+// [token account, metadata, weight, vote record], and adds the weight recorded there,
+// or the DAO's per-NFT weight if its traits haven't been weighed.
+#[derive(Accounts)]
+pub struct NftVote<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    proposal: Account<'info, Proposal>,
+    // Holding a collection NFT makes the owner a member
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = MemberState::LEN,
+        seeds = [b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        mut,
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> NftVote<'info> {
+    pub fn nft_vote(
+        &mut self,
+        vote_type: VoteType,
+        member_state_bump: u8,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let collection = self
+            .config
+            .nft_collection
+            .ok_or(DaoError::InvalidGovernanceMode)?;
+
+        // Check the guardian hasn't paused voting
+        self.config.check_not_paused(PauseTarget::Voting)?;
+        // Check if proposal is open
+        self.proposal.is_open()?;
+        // Check proposal hasn't expired
+        self.proposal.check_expiry()?;
+        // NFT votes are always public
+        self.proposal.check_public_ballot()?;
+        // Ensure at least one NFT votes
+        let weighed = self.config.nft_weight_authority.is_some();
+        let chunk = if weighed { 4 } else { 3 };
+        require!(
+            !remaining_accounts.is_empty() && remaining_accounts.len().is_multiple_of(chunk),
+            DaoError::InvalidVoteAmount
+        );
+
+        let mut weight: u64 = 0;
+        for accounts in remaining_accounts.chunks(chunk) {
+            let (weight_info, record_info) = if weighed {
+                (Some(&accounts[2]), &accounts[3])
+            } else {
+                (None, &accounts[2])
+            };
+            let nft_weight = self.record_nft_vote(
+                collection,
+                vote_type,
+                &accounts[0],
+                &accounts[1],
+                weight_info,
+                record_info,
+            )?;
+            weight = weight
+                .checked_add(nft_weight)
+                .ok_or(DaoError::Overflow)?;
+        }
+
+        // Add vote to proposal
+        self.proposal.add_vote(weight, vote_type)?;
+
+        // Initialize member state if it's a new account
+        if self.member_state.join_date == 0 {
+            let nonce = self.config.next_membership_nonce()?;
+            self.member_state.init(self.owner.key(), nonce, member_state_bump)?;
+        }
+
+        // Award base voting points
+        self.member_state.add_vote_points(BASE_VOTE_POINTS)?;
+
+        // Increase reputation for voting
+        self.member_state.update_reputation(VOTE_REPUTATION_INCREASE)?;
+
+        Ok(())
+    }
+
+    fn record_nft_vote(
+        &self,
+        collection: Pubkey,
+        vote_type: VoteType,
+        token_info: &AccountInfo<'info>,
+        metadata_info: &AccountInfo<'info>,
+        weight_info: Option<&AccountInfo<'info>>,
+        record_info: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        let token_account: Account<'info, TokenAccount> = Account::try_from(token_info)?;
+        let metadata: Account<'info, MetadataAccount> = Account::try_from(metadata_info)?;
+        check_collection_nft(&token_account, &metadata, &self.owner.key(), &collection)?;
+        let weight = match weight_info {
+            Some(info) => self.nft_weight(info, token_account.mint)?,
+            None => self.config.nft_vote_weight,
+        };
+
+        // The record must be the NFT's vote PDA for this proposal
+        let proposal_key = self.proposal.key();
+        let (record_key, bump) = Pubkey::find_program_address(
+            &[b"nft_vote", proposal_key.as_ref(), token_account.mint.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(record_info.key(), record_key, DaoError::InvalidNft);
+
+        // Create the record. This fails if the NFT already voted on the proposal.
+        let seeds = &[
+            &b"nft_vote"[..],
+            &proposal_key.to_bytes()[..],
+            &token_account.mint.to_bytes()[..],
+            &[bump],
+        ];
+        create_pda_account(
+            &self.owner.to_account_info(),
+            record_info,
+            NftVoteRecord::LEN,
+            &self.system_program.to_account_info(),
+            &[&seeds[..]],
+        )?;
+        record_info.try_borrow_mut_data()?[..8].copy_from_slice(&NftVoteRecord::DISCRIMINATOR);
+
+        let mut record: Account<'info, NftVoteRecord> = Account::try_from(record_info)?;
+        record.init(
+            proposal_key,
+            token_account.mint,
+            self.owner.key(),
+            weight,
+            vote_type,
+            bump,
+        )?;
+        record.exit(&crate::ID)?;
+        Ok(weight)
+    }
+
+    // Reads the weight recorded for an NFT's traits. The account must be the NFT's weight
+    // PDA, so a voter can't skip a lower recorded weight by passing another empty account.
+    fn nft_weight(&self, weight_info: &AccountInfo<'info>, nft_mint: Pubkey) -> Result<u64> {
+        let (weight_key, _) = Pubkey::find_program_address(
+            &[b"nft_weight", self.config.key().as_ref(), nft_mint.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(weight_info.key(), weight_key, DaoError::InvalidNftWeight);
+        if weight_info.data_is_empty() {
+            return Ok(self.config.nft_vote_weight);
+        }
+        let nft_weight: Account<'info, NftWeight> = Account::try_from(weight_info)?;
+        Ok(nft_weight.weight)
+    }
+}
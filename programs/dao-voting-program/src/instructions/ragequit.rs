@@ -0,0 +1,187 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address,
+    token::{burn, Burn, Mint, Token, TokenAccount},
+};
+
+use crate::{
+    errors::DaoError,
+    instructions::transfer_from_treasury_vault,
+    state::{setup::DaoSetup, MemberState, PauseTarget},
+};
+
+// Burns a holder's unstaked governance tokens for their pro-rata share of the treasury's
+// SOL and of any treasury token vaults passed in. Proposal fees that may still be refunded
+// aren't part of the share, and the SOL paid out is capped at the issue price of the tokens
+// burned, so tokens bought from the DAO can't be redeemed straight away for more than they
+// cost. Members can't ragequit while they have votes open, but once voting on a proposal
+// they opposed has ended they can clean the vote up and leave before it's executed. Like
+// other payouts, ragequits stop while the guardian has paused payouts.
+//
+// Token vaults are passed as remaining accounts in pairs of
+// (treasury vault, owner's token account for the same mint).
+#[derive(Accounts)]
+pub struct Ragequit<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    owner_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    /// CHECK: The owner's member state, if they've ever staked. It's deserialized and checked for open votes when it exists
+    member_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Ragequit<'info> {
+    pub fn ragequit(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        self.config.check_not_paused(PauseTarget::Payouts)?; // Ragequits pay out of the treasury too
+        require!(amount > 0, DaoError::InvalidRagequitAmount);
+        require!(
+            self.owner_ata.amount >= amount,
+            DaoError::InvalidRagequitAmount
+        );
+        self.check_no_open_votes()?;
+
+        // Shares are computed against the supply before burning
+        let supply = self.mint.supply;
+
+        // Pay out the treasury's token vaults
+        let pairs = remaining_accounts.chunks_exact(2);
+        require!(pairs.remainder().is_empty(), DaoError::InvalidRagequitAccount);
+        let mut paid_mints: Vec<Pubkey> = Vec::new();
+        for pair in pairs {
+            let vault = Account::<TokenAccount>::try_from(&pair[0])?;
+            let destination = Account::<TokenAccount>::try_from(&pair[1])?;
+            require_keys_eq!(
+                vault.key(),
+                get_associated_token_address(&self.treasury.key(), &vault.mint),
+                DaoError::InvalidRagequitAccount
+            );
+            require_keys_eq!(
+                destination.mint,
+                vault.mint,
+                DaoError::InvalidRagequitAccount
+            );
+            require_keys_eq!(
+                destination.owner,
+                self.owner.key(),
+                DaoError::InvalidRagequitAccount
+            );
+            // Each vault can only be paid out once
+            require!(
+                !paid_mints.contains(&vault.mint),
+                DaoError::InvalidRagequitAccount
+            );
+            paid_mints.push(vault.mint);
+
+            let share = pro_rata_share(vault.amount, amount, supply)?;
+            if share > 0 {
+                transfer_from_treasury_vault(
+                    self.token_program.to_account_info(),
+                    pair[0].clone(),
+                    pair[1].clone(),
+                    self.treasury.to_account_info(),
+                    self.config.key(),
+                    self.config.treasury_bump,
+                    share,
+                )?;
+            }
+        }
+
+        // Pay out SOL, keeping the treasury rent exempt and refundable fees escrowed
+        let rent = Rent::get()?.minimum_balance(0);
+        let available = self
+            .treasury
+            .lamports()
+            .saturating_sub(rent)
+            .saturating_sub(self.config.escrowed_fees);
+        let cost = pro_rata_share(self.config.issue_price, amount, self.config.issue_amount)?;
+        let share = pro_rata_share(available, amount, supply)?.min(cost);
+        if share > 0 {
+            self.transfer_from_treasury(share)?;
+        }
+
+        self.burn_tokens(amount)
+    }
+
+    // Members who have never staked have no member state, and so no votes
+    fn check_no_open_votes(&self) -> Result<()> {
+        let member_info = self.member_state.to_account_info();
+        if member_info.data_is_empty() {
+            return Ok(());
+        }
+        let member_state = Account::<MemberState>::try_from(&member_info)?;
+        member_state.check_no_open_votes()
+    }
+
+    fn burn_tokens(&self, amount: u64) -> Result<()> {
+        let accounts = Burn {
+            mint: self.mint.to_account_info(),
+            from: self.owner_ata.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        let ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+        burn(ctx, amount)
+    }
+
+    fn transfer_from_treasury(&self, amount: u64) -> Result<()> {
+        let accounts = Transfer {
+            from: self.treasury.to_account_info(),
+            to: self.owner.to_account_info(),
+        };
+        let seeds = &[
+            &b"treasury"[..],
+            &self.config.key().to_bytes()[..],
+            &[self.config.treasury_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        transfer(ctx, amount)
+    }
+}
+
+// The part of balance owed for amount tokens out of supply, rounded down
+fn pro_rata_share(balance: u64, amount: u64, supply: u64) -> Result<u64> {
+    let share = (balance as u128)
+        .checked_mul(amount as u128)
+        .ok_or(DaoError::Overflow)?
+        .checked_div(supply as u128)
+        .ok_or(DaoError::Overflow)?;
+    u64::try_from(share).map_err(|_| DaoError::Overflow.into())
+}
@@ -1,5 +1,5 @@
 use crate::{
-    constants::{BASE_VOTE_POINTS, VOTE_REPUTATION_DECREASE}, errors::DaoError, state::{setup::DaoSetup, MemberState, Proposal, StakeState, VoteState, VoteType}
+    constants::{BASE_VOTE_POINTS, VOTE_REPUTATION_DECREASE}, errors::DaoError, state::{setup::DaoSetup, MemberState, Proposal, StakeState, VoteState}
 };
 use anchor_lang::prelude::*;
 
@@ -10,27 +10,26 @@ pub struct RemoveOrCleanupVote<'info> {
 
     #[account(
         mut,
-        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref()],
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
         bump = stake_state.state_bump
     )]
     stake_state: Account<'info, StakeState>,
 
-    #[account(
-        mut,
-        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
-        bump = proposal.bump,
-    )]
-    proposal: Account<'info, Proposal>,
+    /// CHECK: This account is closed once the proposal is executed or cleaned up. If not, it is deserialized and checked against the DAO in Proposal::load
+    #[account(mut)]
+    proposal: UncheckedAccount<'info>,
 
     #[account(
         mut,
         close = treasury,
         seeds=[b"vote", proposal.key().as_ref(), owner.key().as_ref()],
-        bump = vote.bump
+        bump = vote.bump,
+        constraint = vote.dao == config.key() && vote.position == stake_state.index @ DaoError::InvalidStakePosition
     )]
     vote: Account<'info, VoteState>,
 
     #[account(
+        mut,
         seeds=[b"treasury", config.key().as_ref()],
         bump = config.treasury_bump
     )]
@@ -54,24 +53,36 @@ pub struct RemoveOrCleanupVote<'info> {
 
 impl<'info> RemoveOrCleanupVote<'info> {
     pub fn cleanup_vote(&mut self) -> Result<()> {
-        if self.proposal.is_open().is_ok() && self.proposal.check_expiry().is_ok() {
-            return err!(DaoError::InvalidProposalStatus);
+        // Votes stay locked until voting and any reveal window are over, so the same tokens
+        // can't be unstaked and revealed again from another wallet. A closed proposal was
+        // executed or cleaned up, so voting on it is over.
+        if let Some(mut proposal) = Proposal::load(&self.proposal, self.config.key())? {
+            if proposal.is_open().is_ok()
+                && (proposal.check_expiry().is_ok() || proposal.check_reveal_ended().is_err())
+            {
+                return err!(DaoError::InvalidProposalStatus);
+            }
         }
         // Remove a vote account from the stake state
-        self.stake_state.remove_account()
+        self.stake_state.remove_account()?;
+        self.member_state.remove_open_vote()
     }
 
     pub fn remove_vote(&mut self) -> Result<()> {
         // Check if the proposal is still open and not expired
-        self.proposal.is_open()?;
-        self.proposal.check_expiry()?;
+        let mut proposal = Proposal::load(&self.proposal, self.config.key())?
+            .ok_or(DaoError::ProposalClosed)?;
+        proposal.is_open()?;
+        proposal.check_expiry()?;
 
-        // Remove the vote from the proposal
-        self.proposal
-            .remove_vote(self.vote.amount, self.vote.vote_type)?;
+        // Remove the vote from the proposal, using the weight it was tallied with
+        proposal.remove_vote(self.vote.weight, self.vote.vote_type)?;
+        // Persist the proposal, as Anchor only does this for the accounts struct
+        proposal.exit(&crate::ID)?;
 
         // Remove the vote account from the stake state
         self.stake_state.remove_account()?;
+        self.member_state.remove_open_vote()?;
 
         // Slash reward points
         self.member_state.slash_vote_points(BASE_VOTE_POINTS)?;
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    state::{setup::DaoSetup, MemberState, PauseTarget, Proposal, StakeState, VoteCommitment, VoteState, VoteType, VoterWeightAction},
+    errors::DaoError,
+    instructions::freeze_receipts,
+    constants::*,
+};
+
+#[derive(Accounts)]
+pub struct RevealVote<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        close = owner,
+        seeds=[b"commit", proposal.key().as_ref(), owner.key().as_ref()],
+        bump = commitment.bump,
+        constraint = commitment.position == stake_state.index @ DaoError::InvalidStakePosition
+    )]
+    commitment: Account<'info, VoteCommitment>,
+    #[account(
+        init,
+        payer = owner,
+        seeds=[b"vote", proposal.key().as_ref(), owner.key().as_ref()],
+        bump,
+        space = VoteState::LEN
+    )]
+    vote: Account<'info, VoteState>,
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump = member_state.bump,
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    /// Required with liquid staking, to freeze the receipts voted with
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Option<Program<'info, Token>>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> RevealVote<'info> {
+    pub fn reveal_vote(
+        &mut self,
+        amount: u64,
+        vote_type: VoteType,
+        salt: [u8; 32],
+        bump: u8
+    ) -> Result<()> {
+        // Check the guardian hasn't paused voting
+        self.config.check_not_paused(PauseTarget::Voting)?;
+        // Check if proposal is open
+        self.proposal.is_open()?;
+        // Check the voting window is over and the reveal window isn't
+        self.proposal.check_reveal_window()?;
+        // Check the revealed vote matches what was committed
+        self.commitment.verify(vote_type, amount, &salt)?;
+        // Ensure vote amount > 0
+        require!(amount > 0, DaoError::InvalidVoteAmount);
+        // Make sure user has enough voting power
+        let voting_power = self.config.voting_power(
+            self.config.key(),
+            &self.stake_state,
+            self.voter_weight_record.as_deref(),
+            VoterWeightAction::CastVote,
+            Some(self.proposal.key()),
+            self.receipt_account.as_deref(),
+        )?;
+        require!(voting_power >= amount, DaoError::InsufficientStake);
+        // Keep the receipts voted with from being voted with again
+        freeze_receipts(
+            &self.config,
+            self.token_program.as_ref(),
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+        )?;
+        // Add vote to proposal, boosted by reputation in hybrid mode
+        let weight = self
+            .config
+            .vote_weight(amount, self.member_state.reputation_score)?;
+        self.proposal.add_vote(weight, vote_type)?;
+        // Initialize vote. The stake account opened by the commitment now belongs to it.
+        self.vote.init(
+            self.config.key(),
+            self.owner.key(),
+            self.stake_state.index,
+            amount,
+            weight,
+            vote_type,
+            bump
+        )?;
+
+        // Award base voting points
+        self.member_state.add_vote_points(BASE_VOTE_POINTS)?;
+
+        // Increase reputation for voting
+        self.member_state.update_reputation(VOTE_REPUTATION_INCREASE)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::ROLE_REVIEWER,
+    errors::DaoError,
+    state::{setup::DaoSetup, MemberState, MilestoneBounty, PauseTarget},
+};
+
+// Lets a milestone bounty's designated reviewer release the next tranche, or abandon
+// the bounty and return its unreleased SOL to the treasury. The reviewer also needs the
+// reviewer role.
+#[derive(Accounts)]
+pub struct ReviewMilestone<'info> {
+    reviewer: Signer<'info>,
+    #[account(
+        seeds=[b"member", config.key().as_ref(), reviewer.key().as_ref()],
+        bump = reviewer_state.bump
+    )]
+    reviewer_state: Account<'info, MemberState>,
+    /// CHECK: This account is not dangerous because it must be the bounty's payee
+    #[account(
+        mut,
+        address = milestone_bounty.payee
+    )]
+    payee: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"milestone", config.key().as_ref(), milestone_bounty.proposal.as_ref()],
+        bump = milestone_bounty.bump
+    )]
+    milestone_bounty: Account<'info, MilestoneBounty>,
+    #[account(
+        mut,
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> ReviewMilestone<'info> {
+    pub fn release_milestone(&mut self) -> Result<()> {
+        self.config.check_not_paused(PauseTarget::Payouts)?;
+        self.reviewer_state.check_role(ROLE_REVIEWER)?;
+        self.milestone_bounty.check_reviewer(self.reviewer.key())?;
+        release_tranche(
+            &mut self.milestone_bounty,
+            &self.payee.to_account_info(),
+            &self.treasury.to_account_info(),
+        )
+    }
+
+    pub fn abandon_milestone(&mut self) -> Result<()> {
+        self.reviewer_state.check_role(ROLE_REVIEWER)?;
+        self.milestone_bounty.check_reviewer(self.reviewer.key())?;
+        self.milestone_bounty.close(self.treasury.to_account_info())
+    }
+}
+
+// Pays the next tranche of a milestone bounty out of its escrow. The escrow is owned by
+// this program, so lamports can be moved directly. Once every tranche is paid, the
+// escrow is closed and its rent returned to the treasury.
+pub fn release_tranche<'info>(
+    milestone_bounty: &mut Account<'info, MilestoneBounty>,
+    payee: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+) -> Result<()> {
+    let amount = milestone_bounty.release_tranche()?;
+
+    let bounty_info = milestone_bounty.to_account_info();
+    **bounty_info.try_borrow_mut_lamports()? = bounty_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(DaoError::Underflow)?;
+    **payee.try_borrow_mut_lamports()? = payee
+        .lamports()
+        .checked_add(amount)
+        .ok_or(DaoError::Overflow)?;
+
+    if milestone_bounty.is_complete() {
+        milestone_bounty.close(treasury.clone())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::MetadataAccount, token::Mint};
+use crate::{
+    errors::DaoError,
+    state::{check_collection_metadata, setup::DaoSetup, NftWeight},
+};
+
+// Lets the DAO's NFT weight authority record the voting weight an NFT's traits earn.
+// Traits live in the NFT's off-chain metadata, so the authority reads them and the
+// program only checks the NFT belongs to the DAO's collection.
+#[derive(Accounts)]
+pub struct SetNftWeight<'info> {
+    #[account(mut)]
+    authority: Signer<'info>,
+    nft_mint: Account<'info, Mint>,
+    nft_metadata: Account<'info, MetadataAccount>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = NftWeight::LEN,
+        seeds = [b"nft_weight", config.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    nft_weight: Account<'info, NftWeight>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> SetNftWeight<'info> {
+    pub fn set_nft_weight(&mut self, weight: u64, bump: u8) -> Result<()> {
+        self.config.check_nft_weight_authority(self.authority.key())?;
+        let collection = self
+            .config
+            .nft_collection
+            .ok_or(DaoError::InvalidGovernanceMode)?;
+        check_collection_metadata(&self.nft_metadata, &self.nft_mint.key(), &collection)?;
+        self.nft_weight.set(self.nft_mint.key(), weight, bump)
+    }
+}
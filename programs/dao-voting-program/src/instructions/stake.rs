@@ -1,12 +1,21 @@
+use std::collections::BTreeMap;
+
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{transfer as transfer_spl, Mint, Token, TokenAccount, Transfer as TransferSpl},
 };
 
-use crate::state::{setup::DaoSetup, MemberState, StakeState};
+use crate::{
+    errors::DaoError,
+    instructions::mint_receipts,
+    state::{setup::DaoSetup, MemberState, RewardPool, StakeState},
+};
 
+// Stakes tokens into a numbered position, creating the position, its vault
+// and the member state on first use
 #[derive(Accounts)]
+#[instruction(index: u64)]
 pub struct Stake<'info> {
     #[account(mut)]
     owner: Signer<'info>,
@@ -17,32 +26,41 @@ pub struct Stake<'info> {
     )]
     owner_ata: Account<'info, TokenAccount>,
     #[account(
-        mut,
-        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref()],
-        bump = stake_state.vault_bump,
+        init_if_needed,
+        payer = owner,
+        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
         token::mint = mint,
         token::authority = auth
     )]
     stake_ata: Account<'info, TokenAccount>,
     #[account(
         seeds=[b"auth", config.key().as_ref(), owner.key().as_ref()],
-        bump = stake_state.auth_bump
+        bump
     )]
     ///CHECK: This is safe. It's just used to sign things
     auth: UncheckedAccount<'info>,
     #[account(
-        mut,
         seeds=[b"mint", config.key().as_ref()],
         bump = config.mint_bump
     )]
     mint: Account<'info, Mint>,
     #[account(
-        mut,
-        seeds=[b"stake", config.seed.to_le_bytes().as_ref()],
-        bump = stake_state.state_bump
+        init_if_needed,
+        payer = owner,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        space = StakeState::LEN
     )]
     stake_state: Account<'info, StakeState>,
     #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Account<'info, RewardPool>,
+    #[account(
+        mut,
         seeds=[b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump
     )]
@@ -55,6 +73,21 @@ pub struct Stake<'info> {
         bump
     )]
     member_state: Account<'info, MemberState>,
+    #[account(
+        mut,
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    /// Holds the staker's receipts with liquid staking, validated against the receipt mint
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt mints
+    dao_auth: Option<UncheckedAccount<'info>>,
     token_program: Program<'info, Token>,
     associated_token_program: Program<'info, AssociatedToken>,
     system_program: Program<'info, System>,
@@ -63,11 +96,23 @@ pub struct Stake<'info> {
 impl<'info> Stake<'info> {
     pub fn deposit_tokens(
         &mut self,
+        index: u64,
         amount: u64,
-        member_state_bump: u8,
+        lock_period: u64,
+        bumps: &BTreeMap<String, u8>,
     ) -> Result<()> {
-        // Stake tokens
-        self.stake_state.stake(amount)?;
+        credit_stake(
+            &mut self.stake_state,
+            &mut self.member_state,
+            &mut self.reward_pool,
+            &mut self.config,
+            self.owner.key(),
+            self.owner.key(),
+            index,
+            amount,
+            lock_period,
+            bumps,
+        )?;
 
         // Transfer tokens
         let accounts = TransferSpl {
@@ -81,42 +126,65 @@ impl<'info> Stake<'info> {
         );
         transfer_spl(ctx, amount)?;
 
-        // Initialize member state if it's a new account
-        if self.member_state.join_date == 0 {
-            self.member_state.init(
-                self.owner.key(),
-                member_state_bump,
-            )?;
-        }
-
-        Ok(())
-
-    } 
-
-    pub fn withdraw_tokens(&mut self, amount: u64) -> Result<()> {
-        self.stake_state.unstake(amount)?;
-
-        let accounts = TransferSpl {
-            from: self.stake_ata.to_account_info(),
-            to: self.owner_ata.to_account_info(),
-            authority: self.auth.to_account_info(),
-        };
-
-        let seeds = &[
-            &b"auth"[..],
-            &self.config.key().to_bytes()[..],
-            &self.auth.key().to_bytes()[..],
-            &[self.stake_state.auth_bump],
-        ];
+        mint_receipts(
+            &self.config,
+            &self.token_program,
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+            self.owner.key(),
+            amount,
+        )
+    }
+}
 
-        let signer_seeds = &[&seeds[..]];
+// Credits staked tokens to an owner's position, creating the position and member state
+// on first use and extending the position's lock. Each position is only ever funded by
+// the wallet that created it.
+pub fn credit_stake(
+    stake_state: &mut Account<StakeState>,
+    member_state: &mut Account<MemberState>,
+    reward_pool: &mut Account<RewardPool>,
+    config: &mut Account<DaoSetup>,
+    owner: Pubkey,
+    funder: Pubkey,
+    index: u64,
+    amount: u64,
+    lock_period: u64,
+    bumps: &BTreeMap<String, u8>,
+) -> Result<()> {
+    config.check_lock_period(lock_period)?;
 
-        let ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            accounts,
-            signer_seeds,
-        );
+    // Initialize member state if it's a new account
+    if member_state.join_date == 0 {
+        member_state.init(
+            owner,
+            config.next_membership_nonce()?,
+            *bumps.get("member_state").ok_or(DaoError::BumpError)?,
+        )?;
+    }
 
-        transfer_spl(ctx, amount)
+    // Initialize the stake position if it's a new account
+    if stake_state.owner == Pubkey::default() {
+        stake_state.init(
+            owner,
+            funder,
+            index,
+            lock_period,
+            *bumps.get("stake_state").ok_or(DaoError::BumpError)?,
+            *bumps.get("stake_ata").ok_or(DaoError::BumpError)?,
+            *bumps.get("auth").ok_or(DaoError::BumpError)?,
+        )?;
+        member_state.add_stake_position()?;
+    } else {
+        stake_state.check_funder(funder)?;
+        stake_state.extend_lock(lock_period)?;
     }
+
+    // Stake tokens, settling rewards earned at the previous amount first
+    let reward_per_token = reward_pool.reward_per_token;
+    stake_state.settle_rewards(reward_per_token)?;
+    stake_state.stake(amount)?;
+    stake_state.reset_reward_debt(reward_per_token)?;
+    reward_pool.stake(amount)
 }
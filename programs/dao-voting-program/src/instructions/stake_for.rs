@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{transfer as transfer_spl, Mint, Token, TokenAccount, Transfer as TransferSpl},
+};
+
+use crate::{
+    errors::DaoError,
+    instructions::{credit_stake, mint_receipts},
+    state::{setup::DaoSetup, MemberState, RewardPool, StakeState},
+};
+
+// Stakes the payer's tokens into a beneficiary's numbered position, creating the
+// position, its vault and the beneficiary's member state on first use
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct StakeFor<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer
+    )]
+    payer_ata: Account<'info, TokenAccount>,
+    /// CHECK: Any wallet can receive staked tokens
+    beneficiary: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [b"vault", config.key().as_ref(), beneficiary.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = auth
+    )]
+    stake_ata: Account<'info, TokenAccount>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    ///CHECK: This is safe. It's just used to sign things
+    auth: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds=[b"stake", config.key().as_ref(), beneficiary.key().as_ref(), index.to_le_bytes().as_ref()],
+        bump,
+        space = StakeState::LEN
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Account<'info, RewardPool>,
+    #[account(
+        mut,
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MemberState::LEN,
+        seeds = [b"member", config.key().as_ref(), beneficiary.key().as_ref()],
+        bump
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        mut,
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    /// Receives the beneficiary's receipts with liquid staking, validated against the receipt mint
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt mints
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Program<'info, Token>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> StakeFor<'info> {
+    pub fn stake_for(
+        &mut self,
+        index: u64,
+        amount: u64,
+        lock_period: u64,
+        bumps: &BTreeMap<String, u8>,
+    ) -> Result<()> {
+        // A lock can only be set on a new position, so a payer can't lock up tokens
+        // the beneficiary already staked
+        require!(
+            lock_period == 0 || self.stake_state.owner == Pubkey::default(),
+            DaoError::InvalidLockPeriod
+        );
+
+        credit_stake(
+            &mut self.stake_state,
+            &mut self.member_state,
+            &mut self.reward_pool,
+            &mut self.config,
+            self.beneficiary.key(),
+            self.payer.key(),
+            index,
+            amount,
+            lock_period,
+            bumps,
+        )?;
+
+        // Transfer tokens from the payer
+        let accounts = TransferSpl {
+            from: self.payer_ata.to_account_info(),
+            to: self.stake_ata.to_account_info(),
+            authority: self.payer.to_account_info()
+        };
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            accounts
+        );
+        transfer_spl(ctx, amount)?;
+
+        mint_receipts(
+            &self.config,
+            &self.token_program,
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+            self.beneficiary.key(),
+            amount,
+        )
+    }
+}
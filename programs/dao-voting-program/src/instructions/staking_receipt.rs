@@ -0,0 +1,165 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{
+    burn, freeze_account, mint_to, thaw_account, Burn, FreezeAccount, Mint, MintTo, ThawAccount,
+    Token, TokenAccount,
+};
+
+use crate::{errors::DaoError, state::setup::DaoSetup};
+
+// With liquid staking, mints one receipt token per staked token to the staker,
+// signed by the DAO authority PDA. Does nothing otherwise.
+pub fn mint_receipts<'info>(
+    config: &Account<'info, DaoSetup>,
+    token_program: &Program<'info, Token>,
+    receipt_mint: Option<&Account<'info, Mint>>,
+    receipt_account: Option<&Account<'info, TokenAccount>>,
+    dao_auth: Option<&UncheckedAccount<'info>>,
+    owner: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    if !config.liquid_staking {
+        return Ok(());
+    }
+    let (receipt_mint, receipt_account, dao_auth) = match (receipt_mint, receipt_account, dao_auth) {
+        (Some(mint), Some(account), Some(auth)) => (mint, account, auth),
+        _ => return err!(DaoError::InvalidReceiptAccount),
+    };
+    config.check_receipt_account(config.key(), receipt_account, owner)?;
+
+    let accounts = MintTo {
+        mint: receipt_mint.to_account_info(),
+        to: receipt_account.to_account_info(),
+        authority: dao_auth.to_account_info(),
+    };
+
+    let seeds = &[
+        &b"auth"[..],
+        &config.key().to_bytes()[..],
+        &[config.auth_bump],
+    ];
+
+    let signer_seeds = &[&seeds[..]];
+
+    let ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        accounts,
+        signer_seeds,
+    );
+
+    mint_to(ctx, amount)
+}
+
+// With liquid staking, burns the receipts for the unstaked tokens from the staker's
+// receipt account. Does nothing otherwise. A receipt account frozen by open votes is
+// thawed for the burn and frozen again, so tokens above the amount committed to the
+// votes can still be unstaked.
+pub fn burn_receipts<'info>(
+    config: &Account<'info, DaoSetup>,
+    token_program: &Program<'info, Token>,
+    receipt_mint: Option<&Account<'info, Mint>>,
+    receipt_account: Option<&Account<'info, TokenAccount>>,
+    dao_auth: Option<&UncheckedAccount<'info>>,
+    owner: &Signer<'info>,
+    amount: u64,
+) -> Result<()> {
+    if !config.liquid_staking {
+        return Ok(());
+    }
+    let (receipt_mint, receipt_account) = match (receipt_mint, receipt_account) {
+        (Some(mint), Some(account)) => (mint, account),
+        _ => return err!(DaoError::InvalidReceiptAccount),
+    };
+    config.check_receipt_account(config.key(), receipt_account, owner.key())?;
+
+    let frozen = receipt_account.is_frozen();
+    if frozen {
+        let dao_auth = dao_auth.ok_or(DaoError::InvalidReceiptAccount)?;
+        set_frozen(config, token_program, receipt_mint, receipt_account, dao_auth, false)?;
+    }
+
+    let accounts = Burn {
+        mint: receipt_mint.to_account_info(),
+        from: receipt_account.to_account_info(),
+        authority: owner.to_account_info(),
+    };
+
+    let ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+    burn(ctx, amount)?;
+
+    match dao_auth {
+        Some(dao_auth) if frozen => {
+            set_frozen(config, token_program, receipt_mint, receipt_account, dao_auth, true)
+        }
+        _ => Ok(()),
+    }
+}
+
+// With liquid staking, freezes the receipt account a vote was cast with, so the receipts
+// can't be moved to another account and voted with again while the vote is open. The
+// holder thaws it with thaw_receipts once their votes are closed. Does nothing otherwise.
+pub fn freeze_receipts<'info>(
+    config: &Account<'info, DaoSetup>,
+    token_program: Option<&Program<'info, Token>>,
+    receipt_mint: Option<&Account<'info, Mint>>,
+    receipt_account: Option<&Account<'info, TokenAccount>>,
+    dao_auth: Option<&UncheckedAccount<'info>>,
+) -> Result<()> {
+    if !config.liquid_staking {
+        return Ok(());
+    }
+    let (token_program, receipt_mint, receipt_account, dao_auth) =
+        match (token_program, receipt_mint, receipt_account, dao_auth) {
+            (Some(program), Some(mint), Some(account), Some(auth)) => (program, mint, account, auth),
+            _ => return err!(DaoError::InvalidReceiptAccount),
+        };
+    // Already frozen by another open vote
+    if receipt_account.is_frozen() {
+        return Ok(());
+    }
+    set_frozen(config, token_program, receipt_mint, receipt_account, dao_auth, true)
+}
+
+// Freezes or thaws a receipt account, signed by the DAO authority PDA
+fn set_frozen<'info>(
+    config: &Account<'info, DaoSetup>,
+    token_program: &Program<'info, Token>,
+    receipt_mint: &Account<'info, Mint>,
+    receipt_account: &Account<'info, TokenAccount>,
+    dao_auth: &UncheckedAccount<'info>,
+    frozen: bool,
+) -> Result<()> {
+    let seeds = &[
+        &b"auth"[..],
+        &config.key().to_bytes()[..],
+        &[config.auth_bump],
+    ];
+
+    let signer_seeds = &[&seeds[..]];
+
+    if frozen {
+        let accounts = FreezeAccount {
+            account: receipt_account.to_account_info(),
+            mint: receipt_mint.to_account_info(),
+            authority: dao_auth.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        freeze_account(ctx)
+    } else {
+        let accounts = ThawAccount {
+            account: receipt_account.to_account_info(),
+            mint: receipt_mint.to_account_info(),
+            authority: dao_auth.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        thaw_account(ctx)
+    }
+}
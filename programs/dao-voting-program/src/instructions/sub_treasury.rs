@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ROLE_ADMIN, ROLE_STEWARD},
+    errors::DaoError,
+    state::{setup::DaoSetup, MemberState, PauseTarget, SubTreasury},
+};
+
+// Creates an unfunded sub-treasury for a working group and its stewards.
+// It holds nothing until a governance proposal funds it. Only admins can create one
+// directly, so names can't be squatted; other members go through a CreateSubTreasury proposal.
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateSubTreasury<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(
+        seeds=[b"member", config.key().as_ref(), payer.key().as_ref()],
+        bump = payer_state.bump
+    )]
+    payer_state: Account<'info, MemberState>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"sub_treasury", config.key().as_ref(), name.as_bytes()],
+        bump,
+        space = SubTreasury::LEN
+    )]
+    sub_treasury: Account<'info, SubTreasury>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CreateSubTreasury<'info> {
+    pub fn create_sub_treasury(
+        &mut self,
+        name: String,
+        stewards: Vec<Pubkey>,
+        bump: u8,
+    ) -> Result<()> {
+        self.payer_state.check_role(ROLE_ADMIN)?;
+        self.sub_treasury.init(name, stewards, bump)
+    }
+}
+
+// Lets a steward with the steward role spend from a sub-treasury within its current budget
+#[derive(Accounts)]
+pub struct SpendSubTreasury<'info> {
+    steward: Signer<'info>,
+    #[account(
+        seeds=[b"member", config.key().as_ref(), steward.key().as_ref()],
+        bump = steward_state.bump
+    )]
+    steward_state: Account<'info, MemberState>,
+    /// CHECK: Any account can receive SOL
+    #[account(mut)]
+    recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"sub_treasury", config.key().as_ref(), sub_treasury.name.as_bytes()],
+        bump = sub_treasury.bump
+    )]
+    sub_treasury: Account<'info, SubTreasury>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> SpendSubTreasury<'info> {
+    pub fn spend(&mut self, amount: u64) -> Result<()> {
+        self.config.check_not_paused(PauseTarget::Payouts)?;
+        self.steward_state.check_role(ROLE_STEWARD)?;
+        self.sub_treasury.spend(self.steward.key(), amount)?;
+        move_lamports(
+            &self.sub_treasury.to_account_info(),
+            &self.recipient.to_account_info(),
+            amount,
+        )
+    }
+}
+
+// Returns a sub-treasury's unspent funds to the main treasury once its period has ended.
+// Anyone can crank it.
+#[derive(Accounts)]
+pub struct ReturnSubTreasuryFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"sub_treasury", config.key().as_ref(), sub_treasury.name.as_bytes()],
+        bump = sub_treasury.bump
+    )]
+    sub_treasury: Account<'info, SubTreasury>,
+    #[account(
+        mut,
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> ReturnSubTreasuryFunds<'info> {
+    pub fn return_funds(&mut self) -> Result<()> {
+        self.sub_treasury.check_period_ended()?;
+        return_unspent_funds(&mut self.sub_treasury, &self.treasury.to_account_info())
+    }
+}
+
+// Moves everything above the rent-exempt minimum from a sub-treasury back to the
+// main treasury and clears its budget
+pub fn return_unspent_funds<'info>(
+    sub_treasury: &mut Account<'info, SubTreasury>,
+    treasury: &AccountInfo<'info>,
+) -> Result<()> {
+    sub_treasury.end_period();
+    let sub_treasury_info = sub_treasury.to_account_info();
+    let rent = Rent::get()?.minimum_balance(sub_treasury_info.data_len());
+    let unspent = sub_treasury_info.lamports().saturating_sub(rent);
+    move_lamports(&sub_treasury_info, treasury, unspent)
+}
+
+// The sub-treasury is owned by this program, so lamports can be moved directly
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(DaoError::Underflow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(DaoError::Overflow)?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{thaw_account, Mint, ThawAccount, Token, TokenAccount};
+
+use crate::state::{setup::DaoSetup, MemberState};
+
+// With liquid staking, thaws a receipt account frozen by a vote once the holder has no
+// votes or commitments left open, so the receipts can be transferred again
+#[derive(Accounts)]
+pub struct ThawReceipts<'info> {
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = owner
+    )]
+    receipt_account: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"member", config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    /// CHECK: The owner's member state, if they're still a member. It's deserialized and checked for open votes when it exists
+    member_state: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt thaws
+    dao_auth: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> ThawReceipts<'info> {
+    pub fn thaw_receipts(&mut self) -> Result<()> {
+        self.check_no_open_votes()?;
+
+        let accounts = ThawAccount {
+            account: self.receipt_account.to_account_info(),
+            mint: self.receipt_mint.to_account_info(),
+            authority: self.dao_auth.to_account_info(),
+        };
+
+        let seeds = &[
+            &b"auth"[..],
+            &self.config.key().to_bytes()[..],
+            &[self.config.auth_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        thaw_account(ctx)
+    }
+
+    // Holders who left the DAO have no member state, and so no votes
+    fn check_no_open_votes(&self) -> Result<()> {
+        let member_info = self.member_state.to_account_info();
+        if member_info.data_is_empty() {
+            return Ok(());
+        }
+        let member_state = Account::<MemberState>::try_from(&member_info)?;
+        member_state.check_no_open_votes()
+    }
+}
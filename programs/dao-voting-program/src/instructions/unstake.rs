@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer as transfer_spl, Mint, Token, TokenAccount, Transfer as TransferSpl};
+
+use crate::{
+    instructions::burn_receipts,
+    state::{setup::DaoSetup, RewardPool, StakeState},
+};
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    owner_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.vault_bump,
+        token::mint = mint,
+        token::authority = auth
+    )]
+    stake_ata: Account<'info, TokenAccount>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref(), owner.key().as_ref()],
+        bump = stake_state.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign things
+    auth: UncheckedAccount<'info>,
+    #[account(
+        seeds=[b"mint", config.key().as_ref()],
+        bump = config.mint_bump
+    )]
+    mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"rewards", config.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    reward_pool: Account<'info, RewardPool>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    #[account(
+        mut,
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    /// Holds the staker's receipts with liquid staking, validated against the receipt mint
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt thaws and freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> Unstake<'info> {
+    pub fn withdraw_tokens(&mut self, amount: u64) -> Result<()> {
+        // Unstake tokens, settling rewards earned at the previous amount first
+        let reward_per_token = self.reward_pool.reward_per_token;
+        self.stake_state.settle_rewards(reward_per_token)?;
+        self.stake_state.unstake(amount)?;
+        self.stake_state.reset_reward_debt(reward_per_token)?;
+        self.reward_pool.unstake(amount)?;
+
+        burn_receipts(
+            &self.config,
+            &self.token_program,
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+            &self.owner,
+            amount,
+        )?;
+
+        withdraw_from_vault(
+            &self.token_program,
+            &self.stake_ata,
+            self.owner_ata.to_account_info(),
+            self.auth.to_account_info(),
+            self.config.key(),
+            self.owner.key(),
+            self.stake_state.auth_bump,
+            amount,
+        )
+    }
+}
+
+// Transfers tokens out of a stake vault, signing with the owner's stake authority PDA
+pub fn withdraw_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    stake_ata: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    stake_auth: AccountInfo<'info>,
+    config: Pubkey,
+    owner: Pubkey,
+    auth_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let accounts = TransferSpl {
+        from: stake_ata.to_account_info(),
+        to: destination,
+        authority: stake_auth,
+    };
+
+    let seeds = &[
+        &b"auth"[..],
+        &config.to_bytes()[..],
+        &owner.to_bytes()[..],
+        &[auth_bump],
+    ];
+
+    let signer_seeds = &[&seeds[..]];
+
+    let ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        accounts,
+        signer_seeds,
+    );
+
+    transfer_spl(ctx, amount)
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::{
+    constants::ROLE_COUNCIL,
+    state::{setup::DaoSetup, MemberState, Proposal},
+};
+
+// Finalizes a proposal whose vote is over, recording when it was decided. With a council,
+// succeeded proposals have to be queued this way and wait out the veto window before
+// they can be executed. Anyone can crank it.
+#[derive(Accounts)]
+pub struct QueueProposal<'info> {
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> QueueProposal<'info> {
+    pub fn queue_proposal(&mut self) -> Result<()> {
+        self.proposal.try_finalize()?;
+        self.proposal.is_succeeded()
+    }
+}
+
+// Lets a council member veto a succeeded proposal within the veto window.
+// The vetoed proposal can't be executed, and cleaning it up slashes the proposer's bond.
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    council_member: Signer<'info>,
+    /// Required when the council is a token mint, validated in DaoSetup::check_council_member
+    council_token_account: Option<Account<'info, TokenAccount>>,
+    /// Required for members given the council role by governance
+    #[account(
+        seeds=[b"member", config.key().as_ref(), council_member.key().as_ref()],
+        bump = member_state.bump
+    )]
+    member_state: Option<Account<'info, MemberState>>,
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+}
+
+impl<'info> VetoProposal<'info> {
+    pub fn veto_proposal(&mut self) -> Result<()> {
+        self.config.check_council_member(
+            self.council_member.key(),
+            self.council_token_account.as_deref(),
+            self.member_state
+                .as_ref()
+                .is_some_and(|member_state| member_state.has_role(ROLE_COUNCIL)),
+        )?;
+        self.proposal.try_finalize()?;
+        self.proposal.veto(self.config.veto_window)
+    }
+}
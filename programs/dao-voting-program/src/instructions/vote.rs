@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use crate::{
-    state::{setup::DaoSetup, Proposal, StakeState, VoteState, MemberState, VoteType},
+    state::{setup::DaoSetup, PauseTarget, Proposal, StakeState, VoteState, MemberState, VoteRationale, VoteType, VoterWeightAction},
     errors::DaoError,
+    events::VoteRationaleRecorded,
+    instructions::freeze_receipts,
     constants::*,
 };
 
@@ -11,7 +14,7 @@ pub struct Vote<'info> {
     owner: Signer<'info>,
     #[account(
         mut,
-        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref()],
+        seeds=[b"stake", config.key().as_ref(), owner.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
         bump = stake_state.state_bump
     )]
     stake_state: Account<'info, StakeState>,
@@ -40,6 +43,24 @@ pub struct Vote<'info> {
         bump = config.config_bump
     )]
     config: Account<'info, DaoSetup>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    /// Required with liquid staking, to freeze the receipts voted with
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Option<Program<'info, Token>>,
     system_program: Program<'info, System>
 }
 
@@ -50,32 +71,119 @@ impl<'info> Vote<'info> {
         vote_type: VoteType,
         bump: u8
     ) -> Result<()> {
-        // Check if proposal is open
-        self.proposal.is_open()?;
-        // Check proposal hasn't expired
-        self.proposal.check_expiry()?;
-        // Ensure vote amount > 0
-        require!(amount > 0, DaoError::InvalidVoteAmount);
-        // Add vote to proposal
-        self.proposal.add_vote(amount, vote_type)?;
-        // Make sure user has staked
-        self.stake_state.check_stake_amount(amount)?;
-        // Add a vote account to the stake state
-        self.stake_state.add_account()?;
-        // Initialize vote
-        self.vote.init(
+        let voting_power = self.config.voting_power(
+            self.config.key(),
+            &self.stake_state,
+            self.voter_weight_record.as_deref(),
+            VoterWeightAction::CastVote,
+            Some(self.proposal.key()),
+            self.receipt_account.as_deref(),
+        )?;
+
+        cast_vote(
+            &self.config,
+            &mut self.proposal,
+            &mut self.stake_state,
+            &mut self.vote,
+            &mut self.member_state,
             self.owner.key(),
             amount,
             vote_type,
-            bump
+            voting_power,
+            bump,
         )?;
 
-        // Award base voting points
-        self.member_state.add_vote_points(BASE_VOTE_POINTS)?;
-
-        // Increase reputation for voting
-        self.member_state.update_reputation(VOTE_REPUTATION_INCREASE)?;
+        // Keep the receipts voted with from being voted with again
+        freeze_receipts(
+            &self.config,
+            self.token_program.as_ref(),
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+        )
+    }
 
-        Ok(())
+    pub fn vote_with_rationale(
+        &mut self,
+        amount: u64,
+        vote_type: VoteType,
+        rationale: VoteRationale,
+        bump: u8
+    ) -> Result<()> {
+        self.vote(amount, vote_type, bump)?;
+        record_rationale(&mut self.vote, self.proposal.key(), rationale)
     }
-}
\ No newline at end of file
+}
+
+// Validates a vote and records it on the proposal, stake, vote and member state.
+// Shared by every instruction that casts a vote so the rules stay identical.
+pub fn cast_vote(
+    config: &Account<DaoSetup>,
+    proposal: &mut Proposal,
+    stake_state: &mut StakeState,
+    vote: &mut VoteState,
+    member_state: &mut MemberState,
+    owner: Pubkey,
+    amount: u64,
+    vote_type: VoteType,
+    voting_power: u64,
+    bump: u8,
+) -> Result<()> {
+    // Check the guardian hasn't paused voting
+    config.check_not_paused(PauseTarget::Voting)?;
+    // Check if proposal is open
+    proposal.is_open()?;
+    // Check proposal hasn't expired
+    proposal.check_expiry()?;
+    // Sealed ballots are cast through commit_vote
+    proposal.check_public_ballot()?;
+    // Ensure vote amount > 0
+    require!(amount > 0, DaoError::InvalidVoteAmount);
+    // Make sure user has enough voting power
+    require!(voting_power >= amount, DaoError::InsufficientStake);
+    // Add vote to proposal, boosted by reputation in hybrid mode
+    let weight = config.vote_weight(amount, member_state.reputation_score)?;
+    proposal.add_vote(weight, vote_type)?;
+    // Add a vote account to the stake state and lock the tokens voted with
+    stake_state.add_account()?;
+    stake_state.commit(amount)?;
+    member_state.add_open_vote()?;
+    // Initialize vote
+    vote.init(
+        config.key(),
+        owner,
+        stake_state.index,
+        amount,
+        weight,
+        vote_type,
+        bump
+    )?;
+
+    // Award base voting points
+    member_state.add_vote_points(BASE_VOTE_POINTS)?;
+
+    // Increase reputation for voting
+    member_state.update_reputation(VOTE_REPUTATION_INCREASE)?;
+
+    Ok(())
+}
+
+// Stores a rationale on a freshly cast vote and publishes it as an event
+pub fn record_rationale(
+    vote: &mut VoteState,
+    proposal: Pubkey,
+    rationale: VoteRationale,
+) -> Result<()> {
+    vote.set_rationale(rationale.clone())?;
+
+    emit!(VoteRationaleRecorded {
+        proposal,
+        voter: vote.owner,
+        amount: vote.amount,
+        vote_type: vote.vote_type,
+        uri: rationale.uri,
+        content_hash: rationale.content_hash,
+    });
+
+    Ok(())
+}
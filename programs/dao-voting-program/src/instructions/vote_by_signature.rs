@@ -0,0 +1,188 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        ed25519_program,
+        instruction::Instruction,
+        sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::{
+    errors::DaoError,
+    instructions::{cast_vote, freeze_receipts},
+    state::{setup::DaoSetup, MemberState, Proposal, StakeState, VoteMessage, VoteState, VoteType, VoterWeightAction},
+};
+
+// Size of the ed25519 instruction header (num_signatures + padding) plus one offsets entry
+const ED25519_HEADER_LENGTH: usize = 16;
+
+#[derive(Accounts)]
+pub struct VoteBySignature<'info> {
+    // Submits the transaction and pays for the vote account on the voter's behalf
+    #[account(mut)]
+    relayer: Signer<'info>,
+    /// CHECK: This account is not dangerous because its signature is verified through the ed25519 program
+    voter: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds=[b"stake", config.key().as_ref(), voter.key().as_ref(), stake_state.index.to_le_bytes().as_ref()],
+        bump = stake_state.state_bump
+    )]
+    stake_state: Account<'info, StakeState>,
+    #[account(
+        mut,
+        seeds=[b"proposal", config.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = relayer,
+        seeds=[b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        space = VoteState::LEN
+    )]
+    vote: Account<'info, VoteState>,
+    #[account(
+        mut,
+        seeds=[b"member", config.key().as_ref(), voter.key().as_ref()],
+        bump = member_state.bump,
+    )]
+    member_state: Account<'info, MemberState>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    /// CHECK: This account is not dangerous because the address is checked against the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    instructions: UncheckedAccount<'info>,
+    /// CHECK: Required when the DAO uses a voter-weight plugin. Ownership and contents are validated in DaoSetup::voting_power
+    voter_weight_record: Option<UncheckedAccount<'info>>,
+    /// Required with liquid staking, validated in DaoSetup::voting_power
+    #[account(mut)]
+    receipt_account: Option<Account<'info, TokenAccount>>,
+    /// Required with liquid staking, to freeze the receipts voted with
+    #[account(
+        seeds = [b"receipt", config.key().as_ref()],
+        bump = config.receipt_mint_bump
+    )]
+    receipt_mint: Option<Account<'info, Mint>>,
+    #[account(
+        seeds=[b"auth", config.key().as_ref()],
+        bump = config.auth_bump
+    )]
+    ///CHECK: This is safe. It's just used to sign receipt freezes
+    dao_auth: Option<UncheckedAccount<'info>>,
+    token_program: Option<Program<'info, Token>>,
+    system_program: Program<'info, System>
+}
+
+impl<'info> VoteBySignature<'info> {
+    pub fn vote_by_signature(
+        &mut self,
+        amount: u64,
+        vote_type: VoteType,
+        nonce: u64,
+        bump: u8
+    ) -> Result<()> {
+        // Rebuild the message the voter is expected to have signed
+        let message = VoteMessage {
+            dao: self.config.key(),
+            proposal_id: self.proposal.id,
+            position: self.stake_state.index,
+            vote_type,
+            amount,
+            nonce,
+        }
+        .try_to_vec()
+        .map_err(|_| DaoError::SerializationError)?;
+
+        // The ed25519 program must have verified that signature in the previous instruction
+        self.verify_signature(&message)?;
+
+        // Consume the nonce so the signed message can't be replayed
+        self.member_state.use_nonce(nonce)?;
+
+        let voting_power = self.config.voting_power(
+            self.config.key(),
+            &self.stake_state,
+            self.voter_weight_record.as_deref(),
+            VoterWeightAction::CastVote,
+            Some(self.proposal.key()),
+            self.receipt_account.as_deref(),
+        )?;
+
+        cast_vote(
+            &self.config,
+            &mut self.proposal,
+            &mut self.stake_state,
+            &mut self.vote,
+            &mut self.member_state,
+            self.voter.key(),
+            amount,
+            vote_type,
+            voting_power,
+            bump,
+        )?;
+
+        // Keep the receipts voted with from being voted with again
+        freeze_receipts(
+            &self.config,
+            self.token_program.as_ref(),
+            self.receipt_mint.as_ref(),
+            self.receipt_account.as_ref(),
+            self.dao_auth.as_ref(),
+        )
+    }
+
+    fn verify_signature(&self, message: &[u8]) -> Result<()> {
+        let instructions = self.instructions.to_account_info();
+        let current_index = load_current_index_checked(&instructions)?;
+        require!(current_index > 0, DaoError::InvalidSignatureInstruction);
+        let ix = load_instruction_at_checked((current_index - 1) as usize, &instructions)?;
+        verify_ed25519_ix(&ix, &self.voter.key(), message)
+    }
+}
+
+// Checks that an ed25519 program instruction verified a single signature by
+// `signer` over exactly `message`, with all data contained in the instruction itself
+fn verify_ed25519_ix(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require_keys_eq!(ix.program_id, ed25519_program::ID, DaoError::InvalidSignatureInstruction);
+    require!(ix.accounts.is_empty(), DaoError::InvalidSignatureInstruction);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_HEADER_LENGTH && data[0] == 1,
+        DaoError::InvalidSignatureInstruction
+    );
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+
+    // Signature, key and message must all live in the ed25519 instruction's own data
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        DaoError::InvalidSignatureInstruction
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(DaoError::InvalidSignatureInstruction)?;
+    require!(public_key == signer.as_ref(), DaoError::InvalidSignatureInstruction);
+
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(DaoError::InvalidSignatureInstruction)?;
+    require!(signed_message == message, DaoError::InvalidVoteMessage);
+
+    Ok(())
+}
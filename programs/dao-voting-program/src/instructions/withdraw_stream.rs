@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    errors::DaoError,
+    instructions::{close_treasury_token_account, transfer_from_treasury_vault},
+    state::{setup::DaoSetup, PauseTarget, PaymentStream},
+};
+
+// Pays everything vested so far to a stream's recipient. Anyone can crank it.
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    /// CHECK: This account is not dangerous because it must be the stream's recipient
+    #[account(
+        mut,
+        address = payment_stream.recipient
+    )]
+    recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"stream", config.key().as_ref(), payment_stream.proposal.as_ref()],
+        bump = payment_stream.bump
+    )]
+    payment_stream: Account<'info, PaymentStream>,
+    // Required for token streams: the stream's escrow and the recipient's token account
+    #[account(mut)]
+    stream_vault: Option<Account<'info, TokenAccount>>,
+    #[account(mut)]
+    recipient_token_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds=[b"treasury", config.key().as_ref()],
+        bump = config.treasury_bump
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(
+        seeds=[b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump
+    )]
+    config: Account<'info, DaoSetup>,
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawStream<'info> {
+    pub fn withdraw_stream(&mut self) -> Result<()> {
+        self.config.check_not_paused(PauseTarget::Payouts)?;
+        let dao = self.config.key();
+        let amount = self.payment_stream.withdraw()?;
+
+        match self.payment_stream.mint {
+            // The stream is owned by this program, so lamports can be moved directly
+            None => {
+                let stream_info = self.payment_stream.to_account_info();
+                let recipient_info = self.recipient.to_account_info();
+                **stream_info.try_borrow_mut_lamports()? = stream_info
+                    .lamports()
+                    .checked_sub(amount)
+                    .ok_or(DaoError::Underflow)?;
+                **recipient_info.try_borrow_mut_lamports()? = recipient_info
+                    .lamports()
+                    .checked_add(amount)
+                    .ok_or(DaoError::Overflow)?;
+            }
+            Some(mint) => {
+                let (stream_vault, recipient_token_account) = self
+                    .stream_vault
+                    .as_ref()
+                    .zip(self.recipient_token_account.as_ref())
+                    .ok_or(DaoError::InvalidStreamAccount)?;
+                self.payment_stream.check_vault(dao, stream_vault)?;
+                require_keys_eq!(recipient_token_account.mint, mint, DaoError::InvalidStreamAccount);
+                require_keys_eq!(
+                    recipient_token_account.owner,
+                    self.payment_stream.recipient,
+                    DaoError::InvalidStreamAccount
+                );
+                transfer_from_treasury_vault(
+                    self.token_program.to_account_info(),
+                    stream_vault.to_account_info(),
+                    recipient_token_account.to_account_info(),
+                    self.treasury.to_account_info(),
+                    dao,
+                    self.config.treasury_bump,
+                    amount,
+                )?;
+                if self.payment_stream.is_finished()? {
+                    close_treasury_token_account(
+                        self.token_program.to_account_info(),
+                        stream_vault.to_account_info(),
+                        self.treasury.to_account_info(),
+                        dao,
+                        self.config.treasury_bump,
+                    )?;
+                }
+            }
+        }
+
+        // The stream's rent goes back to the treasury once it's fully paid
+        if self.payment_stream.is_finished()? {
+            self.payment_stream.close(self.treasury.to_account_info())?;
+        }
+        Ok(())
+    }
+}
@@ -4,8 +4,10 @@ mod instructions;
 use instructions::*;
 mod constants;
 mod errors;
+mod events;
 mod state;
-use crate::state::VoteType;
+mod utils;
+use crate::state::{BallotMode, BatchVoteItem, DaoSettings, PauseTarget, SplitShare, VoteRationale, VoteType};
 
 use crate::{errors::DaoError, state::ProposalType};
 
@@ -14,38 +16,14 @@ declare_id!("HScCa2Qkqn5DRFDXySiwhtTMNJs87hdo8DjsBxvBfyn6");
 #[program]
 pub mod dao_voting_program {
 
-    use state::{MemberStateView, ProposalResults};
+    use state::{MemberStateView, ProposalResults, VoteRationaleView};
 
     use super::*;
 
     // Initialize a DAO
 
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        seed: u64,
-        issue_price: u64,
-        issue_amount: u64,
-        proposal_fee: u64,
-        max_supply: u64,
-        min_quorum: u64,
-        max_expiry: u64,
-    ) -> Result<()> {
-        ctx.accounts.init(
-            seed,
-            &ctx.bumps,
-            issue_price,
-            issue_amount,
-            proposal_fee,
-            max_supply,
-            min_quorum,
-            max_expiry,
-        )
-    }
-
-    // Initialize a stake account for adding DAO tokens
-    pub fn init_stake(ctx: Context<InitializeStake>) -> Result<()> {
-        // Create a stake account
-        ctx.accounts.init(&ctx.bumps)
+    pub fn initialize(ctx: Context<Initialize>, seed: u64, settings: DaoSettings) -> Result<()> {
+        ctx.accounts.init(seed, &ctx.bumps, settings)
     }
 
     // Create a proposal
@@ -57,11 +35,18 @@ pub mod dao_voting_program {
         proposal_type: ProposalType,
         threshold: u64,
         amount: u64,
+        ballot: BallotMode,
         //data: Vec<u8>,
     ) -> Result<()> {
+        // Make NFT holders members the first time they propose
+        ctx.accounts.init_member(*ctx.bumps.get("member_state").ok_or(DaoError::BumpError)?)?;
+
         // Pay a proposal fee to DAO treasury
         ctx.accounts.pay_proposal_fee()?;
 
+        // Lock the proposal bond, if the DAO charges one
+        ctx.accounts.lock_proposal_bond()?;
+
         // Ensure user has actually got tokens staked and create a new proposal
         ctx.accounts.create_proposal(
             id,
@@ -70,20 +55,36 @@ pub mod dao_voting_program {
             proposal_type,
             threshold,
             amount,
+            ballot,
             *ctx.bumps.get("proposal").ok_or(DaoError::BumpError)?,
         )
     }
 
+    // Record the recipients of a split bounty proposal before voting starts
+    pub fn create_bounty_split(ctx: Context<CreateBountySplit>, shares: Vec<SplitShare>) -> Result<()> {
+        let bump = *ctx.bumps.get("bounty_split").ok_or(DaoError::BumpError)?;
+        ctx.accounts.create_bounty_split(shares, bump)
+    }
+
     // Stake get proposal results
     pub fn get_proposal_results(ctx: Context<GetProposalResults>) -> Result<ProposalResults> {
         ctx.accounts.get_results()
     }
 
-    // Stake DAO tokens
-    pub fn stake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
-        let bump = *ctx.bumps.get("member").ok_or(DaoError::BumpError)?;
+    // Stake DAO tokens into a numbered position, creating it if needed and locking it for at least lock_period slots
+    pub fn stake_tokens(ctx: Context<Stake>, index: u64, amount: u64, lock_period: u64) -> Result<()> {
         // Deposit tokens, add stake
-        ctx.accounts.deposit_tokens(amount, bump)
+        ctx.accounts.deposit_tokens(index, amount, lock_period, &ctx.bumps)
+    }
+
+    // Stake DAO tokens into another wallet's numbered position, optionally locking a new position
+    pub fn stake_for(ctx: Context<StakeFor>, index: u64, amount: u64, lock_period: u64) -> Result<()> {
+        ctx.accounts.stake_for(index, amount, lock_period, &ctx.bumps)
+    }
+
+    // Turn down a position another wallet staked for you, returning the tokens to it
+    pub fn reject_stake(ctx: Context<RejectStake>) -> Result<()> {
+        ctx.accounts.reject_stake()
     }
 
     // Vote on a proposal
@@ -95,6 +96,91 @@ pub mod dao_voting_program {
         ctx.accounts.vote(amount, vote_type, bump)
     }
 
+    // Vote on a proposal and publish the reasoning behind the vote
+    pub fn vote_with_rationale(
+        ctx: Context<Vote>,
+        amount: u64,
+        vote_type: VoteType,
+        rationale: VoteRationale,
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("vote").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.vote_with_rationale(amount, vote_type, rationale, bump)
+    }
+
+    // Get a member's vote and the rationale recorded with it
+    pub fn get_vote_rationale(ctx: Context<GetVoteRationale>) -> Result<VoteRationaleView> {
+        ctx.accounts.get_vote_rationale()
+    }
+
+    // Vote on several proposals at once, passing [proposal, vote] pairs as remaining accounts
+    pub fn batch_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchVote<'info>>,
+        votes: Vec<BatchVoteItem>,
+    ) -> Result<()> {
+        ctx.accounts.batch_vote(votes, ctx.remaining_accounts)
+    }
+
+    // Vote with NFTs of the DAO's collection, passing [token account, metadata, vote record]
+    // triples as remaining accounts, or [token account, metadata, weight, vote record] when
+    // the DAO weighs NFTs by their traits
+    pub fn nft_vote<'info>(
+        ctx: Context<'_, '_, '_, 'info, NftVote<'info>>,
+        vote_type: VoteType,
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("member_state").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.nft_vote(vote_type, bump, ctx.remaining_accounts)
+    }
+
+    // Close an NFT vote record once its proposal has been decided
+    pub fn cleanup_nft_vote(ctx: Context<CleanupNftVote>) -> Result<()> {
+        ctx.accounts.cleanup_nft_vote()
+    }
+
+    // Record the voting weight an NFT's traits earn, signed by the DAO's NFT weight authority
+    pub fn set_nft_weight(ctx: Context<SetNftWeight>, weight: u64) -> Result<()> {
+        let bump = *ctx.bumps.get("nft_weight").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.set_nft_weight(weight, bump)
+    }
+
+    // Vote on a proposal with a member's off-chain signature, submitted by a relayer
+    pub fn vote_by_signature(
+        ctx: Context<VoteBySignature>,
+        amount: u64,
+        vote_type: VoteType,
+        nonce: u64,
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("vote").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.vote_by_signature(amount, vote_type, nonce, bump)
+    }
+
+    // Commit a sealed vote on a commit-reveal proposal
+    pub fn commit_vote(ctx: Context<CommitVote>, commitment: [u8; 32]) -> Result<()> {
+        let bump = *ctx.bumps.get("commitment").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.commit_vote(commitment, bump)
+    }
+
+    // Reveal a sealed vote during the reveal window and add it to the tally
+    pub fn reveal_vote(
+        ctx: Context<RevealVote>,
+        amount: u64,
+        vote_type: VoteType,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("vote").ok_or(DaoError::BumpError)?;
+
+        ctx.accounts.reveal_vote(amount, vote_type, salt, bump)
+    }
+
+    // Discard a sealed vote that was never revealed
+    pub fn cleanup_commit(ctx: Context<CleanupCommit>) -> Result<()> {
+        ctx.accounts.cleanup_commit()
+    }
+
     // Close a stake account when you're done with it
     pub fn close_stake_account(ctx: Context<CloseStakeAccount>) -> Result<()> {
         // Create a stake account
@@ -102,15 +188,111 @@ pub mod dao_voting_program {
     }
 
     // Unstake DAO tokens
-    pub fn unstake_tokens(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    pub fn unstake_tokens(ctx: Context<Unstake>, amount: u64) -> Result<()> {
         // Withdraw tokens, remove stake
         ctx.accounts.withdraw_tokens(amount)
     }
 
-    // Execute a proposal
-    pub fn execute_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+    // Unstake everything, close the last stake position and member state and leave the DAO
+    pub fn exit_dao(ctx: Context<ExitDao>) -> Result<()> {
+        ctx.accounts.exit_dao()
+    }
+
+    // Thaw a receipt account frozen by a vote once the holder's votes are closed
+    pub fn thaw_receipts(ctx: Context<ThawReceipts>) -> Result<()> {
+        ctx.accounts.thaw_receipts()
+    }
+
+    // Claim SOL rewards earned by staked tokens
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        ctx.accounts.claim_rewards()
+    }
+
+    // Create the treasury's token account for a mint and its spending tracker
+    pub fn create_treasury_vault(ctx: Context<CreateTreasuryVault>) -> Result<()> {
+        ctx.accounts.create_treasury_vault(&ctx.bumps)
+    }
+
+    // Release the next tranche of a milestone bounty as its reviewer
+    pub fn release_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+        ctx.accounts.release_milestone()
+    }
+
+    // Abandon a milestone bounty as its reviewer, returning unreleased SOL to the treasury
+    pub fn abandon_milestone(ctx: Context<ReviewMilestone>) -> Result<()> {
+        ctx.accounts.abandon_milestone()
+    }
+
+    // Record that a proposal has been decided, starting the council's veto window
+    pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
+        ctx.accounts.queue_proposal()
+    }
+
+    // Veto a succeeded proposal as a council member, blocking its execution
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        ctx.accounts.veto_proposal()
+    }
+
+    // Pause voting, proposal creation or treasury payouts as the guardian
+    pub fn pause(ctx: Context<Guardian>, target: PauseTarget, duration: u64) -> Result<()> {
+        ctx.accounts.pause(target, duration)
+    }
+
+    // Lift a pause early as the guardian
+    pub fn unpause(ctx: Context<Guardian>, target: PauseTarget) -> Result<()> {
+        ctx.accounts.unpause(target)
+    }
+
+    // Burn unstaked governance tokens for a pro-rata share of the treasury.
+    // Treasury token vaults and the owner's matching token accounts are passed in pairs as remaining accounts
+    pub fn ragequit<'info>(
+        ctx: Context<'_, '_, '_, 'info, Ragequit<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.ragequit(amount, ctx.remaining_accounts)
+    }
+
+    // Pay a payment stream's vested amount to its recipient
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        ctx.accounts.withdraw_stream()
+    }
+
+    // Record the sub-treasury name and stewards of a CreateSubTreasury or SetStewards proposal
+    pub fn create_steward_set(
+        ctx: Context<CreateStewardSet>,
+        name: String,
+        stewards: Vec<Pubkey>,
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("steward_set").ok_or(DaoError::BumpError)?;
+        ctx.accounts.create_steward_set(name, stewards, bump)
+    }
+
+    // Create an unfunded sub-treasury for a working group as an admin
+    pub fn create_sub_treasury(
+        ctx: Context<CreateSubTreasury>,
+        name: String,
+        stewards: Vec<Pubkey>,
+    ) -> Result<()> {
+        let bump = *ctx.bumps.get("sub_treasury").ok_or(DaoError::BumpError)?;
+        ctx.accounts.create_sub_treasury(name, stewards, bump)
+    }
+
+    // Spend from a sub-treasury's budget as one of its stewards
+    pub fn spend_sub_treasury(ctx: Context<SpendSubTreasury>, amount: u64) -> Result<()> {
+        ctx.accounts.spend(amount)
+    }
+
+    // Return a sub-treasury's unspent funds to the treasury after its period ends
+    pub fn return_sub_treasury_funds(ctx: Context<ReturnSubTreasuryFunds>) -> Result<()> {
+        ctx.accounts.return_funds()
+    }
+
+    // Execute a proposal. Split bounties pass their recipients as remaining accounts, in order.
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeProposal<'info>>,
+    ) -> Result<()> {
         // Pay a proposal fee to DAO treasury
-        ctx.accounts.execute_proposal()
+        ctx.accounts.execute_proposal(&ctx.bumps, ctx.remaining_accounts)
     }
 
     // Cleanup a failed proposal
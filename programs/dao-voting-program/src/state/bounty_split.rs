@@ -0,0 +1,92 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+
+// The BountySplit account stores the recipients of a SplitBounty proposal, so the
+// accounts passed at execution can be checked against what was voted on
+#[account]
+pub struct BountySplit {
+    // The SplitBounty proposal the recipients belong to
+    pub proposal: Pubkey,
+
+    // The recipients and their fixed amounts or basis-point shares
+    pub shares: Vec<SplitShare>,
+
+    // Bump seed for this split's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl BountySplit {
+    /// Total size of the BountySplit account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // proposal: Pubkey
+        4 + MAX_SPLIT_RECIPIENTS * SplitShare::LEN + // shares: Vec<SplitShare>
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new BountySplit account, checking the shares add up
+    pub fn init(
+        &mut self,
+        proposal: Pubkey,
+        shares: Vec<SplitShare>,
+        total: u64,
+        mode: SplitMode,
+        bump: u8,
+    ) -> Result<()> {
+        require!(
+            !shares.is_empty() && shares.len() <= MAX_SPLIT_RECIPIENTS,
+            DaoError::InvalidBountySplit
+        );
+        let sum = shares
+            .iter()
+            .try_fold(0u64, |sum, share| sum.checked_add(share.value))
+            .ok_or(DaoError::Overflow)?;
+        let expected = match mode {
+            SplitMode::Fixed => total,
+            SplitMode::BasisPoints => BASIS_POINTS,
+        };
+        require!(sum == expected, DaoError::InvalidBountySplit);
+        self.proposal = proposal;
+        self.shares = shares;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Returns the amount owed to each recipient, in order. Rounding dust from
+    // basis-point shares goes to the last recipient.
+    pub fn payouts(&self, total: u64, mode: SplitMode) -> Result<Vec<(Pubkey, u64)>> {
+        let mut payouts = Vec::with_capacity(self.shares.len());
+        let mut paid = 0u64;
+        for (i, share) in self.shares.iter().enumerate() {
+            let amount = match mode {
+                SplitMode::Fixed => share.value,
+                SplitMode::BasisPoints if i == self.shares.len() - 1 => {
+                    total.checked_sub(paid).ok_or(DaoError::Underflow)?
+                }
+                SplitMode::BasisPoints => ((total as u128)
+                    .checked_mul(share.value as u128)
+                    .ok_or(DaoError::Overflow)?
+                    / BASIS_POINTS as u128) as u64,
+            };
+            paid = paid.checked_add(amount).ok_or(DaoError::Overflow)?;
+            payouts.push((share.recipient, amount));
+        }
+        Ok(payouts)
+    }
+}
+
+/// A recipient of a split bounty and its fixed amount or basis-point share
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SplitShare {
+    pub recipient: Pubkey,
+    pub value: u64,
+}
+
+impl SplitShare {
+    pub const LEN: usize = PUBKEY_LENGTH + U64_LENGTH;
+}
+
+/// Enum representing how a split bounty's shares are expressed
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SplitMode {
+    Fixed,       // Each share is an amount of lamports, adding up to the total
+    BasisPoints, // Each share is a fraction of the total in basis points, adding up to 10000
+}
@@ -15,6 +15,11 @@ pub struct MemberState {
     pub proposal_creation_points: u64,
     pub proposal_success_points: u64,
     pub forfeited_points: u64,
+    pub nonce: u64,
+    pub stake_positions: u64,
+    pub open_votes: u64,
+    pub open_proposals: u64,
+    pub roles: u8,
     pub bump: u8,
 }
 
@@ -31,10 +36,15 @@ impl MemberState {
     U64_LENGTH +       // proposal_creation_points: u64
     U64_LENGTH +       // proposal_success_points: u64
     U64_LENGTH +       // forfeited_points: u64
+    U64_LENGTH +       // nonce: u64
+    U64_LENGTH +       // stake_positions: u64
+    U64_LENGTH +       // open_votes: u64
+    U64_LENGTH +       // open_proposals: u64
+    U8_LENGTH +        // roles: u8
     U8_LENGTH +        // bump: u8
     8; // Discriminator (added by Anchor)
 
-    pub fn init(&mut self, address: Pubkey, bump: u8) -> Result<()> {
+    pub fn init(&mut self, address: Pubkey, nonce: u64, bump: u8) -> Result<()> {
         self.address = address;
         self.reward_points = 0;
         self.total_votes_cast = 0;
@@ -47,6 +57,11 @@ impl MemberState {
         self.proposal_creation_points = 0;
         self.proposal_success_points = 0;
         self.forfeited_points = 0;
+        self.nonce = nonce;
+        self.stake_positions = 0;
+        self.open_votes = 0;
+        self.open_proposals = 0;
+        self.roles = 0;
         self.bump = bump;
         Ok(())
     }
@@ -126,6 +141,92 @@ impl MemberState {
         Ok(())
     }
 
+    pub fn add_stake_position(&mut self) -> Result<()> {
+        self.stake_positions = self
+            .stake_positions
+            .checked_add(1)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn remove_stake_position(&mut self) -> Result<()> {
+        self.stake_positions = self
+            .stake_positions
+            .checked_sub(1)
+            .ok_or(DaoError::Underflow)?;
+        Ok(())
+    }
+
+    // Ensures the member has no stake positions left besides the one being closed
+    pub fn check_last_stake_position(&self) -> Result<()> {
+        require!(self.stake_positions == 1, DaoError::AccountsOpen);
+        Ok(())
+    }
+
+    // Counts a vote or commitment the member has open on a proposal
+    pub fn add_open_vote(&mut self) -> Result<()> {
+        self.open_votes = self.open_votes.checked_add(1).ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn remove_open_vote(&mut self) -> Result<()> {
+        self.open_votes = self.open_votes.checked_sub(1).ok_or(DaoError::Underflow)?;
+        Ok(())
+    }
+
+    // Ensures the member has no votes or commitments left open
+    pub fn check_no_open_votes(&self) -> Result<()> {
+        require!(self.open_votes == 0, DaoError::AccountsOpen);
+        Ok(())
+    }
+
+    // Counts a proposal the member created that hasn't been executed or cleaned up yet
+    pub fn add_open_proposal(&mut self) -> Result<()> {
+        self.open_proposals = self
+            .open_proposals
+            .checked_add(1)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn remove_open_proposal(&mut self) -> Result<()> {
+        self.open_proposals = self
+            .open_proposals
+            .checked_sub(1)
+            .ok_or(DaoError::Underflow)?;
+        Ok(())
+    }
+
+    // Ensures the member has no proposals left to execute or clean up, which need their member state
+    pub fn check_no_open_proposals(&self) -> Result<()> {
+        require!(self.open_proposals == 0, DaoError::AccountsOpen);
+        Ok(())
+    }
+
+    // Replaces the member's roles, as decided by governance
+    pub fn set_roles(&mut self, roles: u8) -> Result<()> {
+        require!(roles & !ALL_ROLES == 0, DaoError::InvalidRoles);
+        self.roles = roles;
+        Ok(())
+    }
+
+    pub fn has_role(&self, role: u8) -> bool {
+        self.roles & role == role
+    }
+
+    // Ensures the member has been given the role
+    pub fn check_role(&self, role: u8) -> Result<()> {
+        require!(self.has_role(role), DaoError::MissingRole);
+        Ok(())
+    }
+
+    // Consumes the nonce of a signed vote message so it can't be replayed
+    pub fn use_nonce(&mut self, nonce: u64) -> Result<()> {
+        require!(self.nonce == nonce, DaoError::InvalidNonce);
+        self.nonce = self.nonce.checked_add(1).ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
     pub fn get_member_state(&self) -> MemberStateView {
         MemberStateView {
             address: self.address,
@@ -140,6 +241,11 @@ impl MemberState {
             proposal_creation_points: self.proposal_creation_points,
             proposal_success_points: self.proposal_success_points,
             forfeited_points: self.forfeited_points,
+            nonce: self.nonce,
+            stake_positions: self.stake_positions,
+            open_votes: self.open_votes,
+            open_proposals: self.open_proposals,
+            roles: self.roles,
         }
     }
 }
@@ -158,4 +264,9 @@ pub struct MemberStateView {
     pub proposal_creation_points: u64,
     pub proposal_success_points: u64,
     pub forfeited_points: u64,
+    pub nonce: u64,
+    pub stake_positions: u64,
+    pub open_votes: u64,
+    pub open_proposals: u64,
+    pub roles: u8,
 }
@@ -0,0 +1,89 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+
+// The MilestoneBounty account escrows a bounty's SOL after its proposal passes and
+// releases it to the payee in equal tranches, each approved by the reviewer or a
+// confirm vote. Closing it returns whatever wasn't released to the treasury.
+#[account]
+pub struct MilestoneBounty {
+    // The proposal that approved the bounty
+    pub proposal: Pubkey,
+
+    // The address receiving each tranche
+    pub payee: Pubkey,
+
+    // The key allowed to approve tranches, or the default key if only confirm votes can
+    pub reviewer: Pubkey,
+
+    // The total amount of lamports across all tranches
+    pub total: u64,
+
+    // The number of tranches
+    pub tranches: u8,
+
+    // The number of tranches released so far
+    pub released: u8,
+
+    // Bump seed for this bounty's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl MilestoneBounty {
+    /// Total size of the MilestoneBounty account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // proposal: Pubkey
+        PUBKEY_LENGTH +    // payee: Pubkey
+        PUBKEY_LENGTH +    // reviewer: Pubkey
+        U64_LENGTH +       // total: u64
+        U8_LENGTH +        // tranches: u8
+        U8_LENGTH +        // released: u8
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new MilestoneBounty account
+    pub fn init(
+        &mut self,
+        proposal: Pubkey,
+        payee: Pubkey,
+        reviewer: Pubkey,
+        total: u64,
+        tranches: u8,
+        bump: u8,
+    ) -> Result<()> {
+        require!(tranches > 0, DaoError::InvalidMilestoneBounty);
+        self.proposal = proposal;
+        self.payee = payee;
+        self.reviewer = reviewer;
+        self.total = total;
+        self.tranches = tranches;
+        self.released = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Marks the next tranche as released and returns its amount. The last tranche
+    // carries the remainder of the division.
+    pub fn release_tranche(&mut self) -> Result<u64> {
+        require!(!self.is_complete(), DaoError::InvalidMilestoneBounty);
+        let tranche = self.total / self.tranches as u64;
+        self.released = self.released.checked_add(1).ok_or(DaoError::Overflow)?;
+        if self.is_complete() {
+            Ok(self.total - tranche * (self.tranches as u64 - 1))
+        } else {
+            Ok(tranche)
+        }
+    }
+
+    // Whether every tranche has been released
+    pub fn is_complete(&self) -> bool {
+        self.released >= self.tranches
+    }
+
+    // Ensures the signer is the bounty's designated reviewer
+    pub fn check_reviewer(&self, reviewer: Pubkey) -> Result<()> {
+        require!(
+            self.reviewer != Pubkey::default() && self.reviewer == reviewer,
+            DaoError::InvalidReviewer
+        );
+        Ok(())
+    }
+}
@@ -3,9 +3,27 @@ pub mod proposal;
 pub mod vote;
 pub mod stake;
 pub mod member_state;
+pub mod voter_weight;
+pub mod nft_vote;
+pub mod reward_pool;
+pub mod milestone_bounty;
+pub mod payment_stream;
+pub mod bounty_split;
+pub mod sub_treasury;
+pub mod steward_set;
+pub mod vault_spend;
 
 pub use setup::*;
 pub use proposal::*;
 pub use vote::*;
 pub use stake::*;
-pub use member_state::*;
\ No newline at end of file
+pub use member_state::*;
+pub use voter_weight::*;
+pub use nft_vote::*;
+pub use reward_pool::*;
+pub use milestone_bounty::*;
+pub use payment_stream::*;
+pub use bounty_split::*;
+pub use sub_treasury::*;
+pub use steward_set::*;
+pub use vault_spend::*;
\ No newline at end of file
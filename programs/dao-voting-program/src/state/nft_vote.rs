@@ -0,0 +1,110 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::MetadataAccount, token::TokenAccount};
+
+use super::VoteType;
+
+// The NftVoteRecord account marks an NFT as having voted on a proposal. It is keyed by
+// the NFT mint rather than the holder, so a transferred NFT can't vote a second time.
+#[account]
+pub struct NftVoteRecord {
+    // The proposal the NFT voted on
+    pub proposal: Pubkey,
+
+    // The mint of the NFT that voted
+    pub nft_mint: Pubkey,
+
+    // The holder that cast the vote and paid for this account
+    pub voter: Pubkey,
+
+    // The weight the NFT added to the tally
+    pub weight: u64,
+
+    /// Enum representing the type of vote
+    pub vote_type: VoteType,
+
+    // Bump seed for the record's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl NftVoteRecord {
+    /// Total size of the NftVoteRecord account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // proposal: Pubkey
+        PUBKEY_LENGTH +    // nft_mint: Pubkey
+        PUBKEY_LENGTH +    // voter: Pubkey
+        U64_LENGTH +       // weight: u64
+        ENUM_LENGTH +      // vote_type: VoteType (simple enum, 1 byte)
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new NftVoteRecord account
+    pub fn init(
+        &mut self,
+        proposal: Pubkey,
+        nft_mint: Pubkey,
+        voter: Pubkey,
+        weight: u64,
+        vote_type: VoteType,
+        bump: u8,
+    ) -> Result<()> {
+        self.proposal = proposal;
+        self.nft_mint = nft_mint;
+        self.voter = voter;
+        self.weight = weight;
+        self.vote_type = vote_type;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// The NftWeight account holds the voting weight the DAO's NFT weight authority gave an NFT
+// for its traits, which live in off-chain metadata the program can't read
+#[account]
+pub struct NftWeight {
+    // The mint of the weighed NFT
+    pub nft_mint: Pubkey,
+
+    // The weight the NFT adds to the tally
+    pub weight: u64,
+
+    // Bump seed for the weight's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl NftWeight {
+    /// Total size of the NftWeight account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // nft_mint: Pubkey
+        U64_LENGTH +       // weight: u64
+        U8_LENGTH; // bump: u8
+
+    // Sets the NFT's weight, creating the account on first use
+    pub fn set(&mut self, nft_mint: Pubkey, weight: u64, bump: u8) -> Result<()> {
+        require!(weight > 0, DaoError::InvalidNftWeight);
+        self.nft_mint = nft_mint;
+        self.weight = weight;
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// Ensures `owner` holds the NFT in `token_account` and that it is a verified member of `collection`
+pub fn check_collection_nft(
+    token_account: &TokenAccount,
+    metadata: &MetadataAccount,
+    owner: &Pubkey,
+    collection: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(token_account.owner, *owner, DaoError::InvalidNft);
+    require!(token_account.amount == 1, DaoError::InvalidNft);
+    check_collection_metadata(metadata, &token_account.mint, collection)
+}
+
+// Ensures `metadata` belongs to `mint` and that the NFT is a verified member of `collection`
+pub fn check_collection_metadata(metadata: &MetadataAccount, mint: &Pubkey, collection: &Pubkey) -> Result<()> {
+    require_keys_eq!(metadata.mint, *mint, DaoError::InvalidNft);
+    match &metadata.collection {
+        Some(c) if c.verified && c.key == *collection => Ok(()),
+        _ => err!(DaoError::InvalidNft),
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+// The PaymentStream account escrows a payment created by a passed proposal and vests it
+// to the recipient at a fixed rate between a start and an end, in slots or seconds.
+// SOL streams hold their funds in this account; token streams in a token account
+// derived from it.
+#[account]
+pub struct PaymentStream {
+    // The proposal that created the stream
+    pub proposal: Pubkey,
+
+    // The address receiving the stream
+    pub recipient: Pubkey,
+
+    // The mint of the streamed token, or None for SOL
+    pub mint: Option<Pubkey>,
+
+    // Whether start and end are slots or unix timestamps
+    pub unit: StreamUnit,
+
+    // Amount vested per slot or second
+    pub rate: u64,
+
+    // Slot or timestamp at which vesting starts
+    pub start: u64,
+
+    // Slot or timestamp at which vesting ends
+    pub end: u64,
+
+    // Amount already withdrawn by the recipient
+    pub withdrawn: u64,
+
+    // Bump seed for this stream's Program Derived Address (PDA)
+    pub bump: u8,
+
+    // Bump seed for the token vault's PDA, unused for SOL streams
+    pub vault_bump: u8,
+}
+
+impl PaymentStream {
+    /// Total size of the PaymentStream account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // proposal: Pubkey
+        PUBKEY_LENGTH +    // recipient: Pubkey
+        OPTION_LENGTH + PUBKEY_LENGTH + // mint: Option<Pubkey>
+        ENUM_LENGTH +      // unit: StreamUnit
+        U64_LENGTH +       // rate: u64
+        U64_LENGTH +       // start: u64
+        U64_LENGTH +       // end: u64
+        U64_LENGTH +       // withdrawn: u64
+        U8_LENGTH +        // bump: u8
+        U8_LENGTH; // vault_bump: u8
+
+    // Initializes a new PaymentStream account
+    pub fn init(
+        &mut self,
+        proposal: Pubkey,
+        recipient: Pubkey,
+        mint: Option<Pubkey>,
+        unit: StreamUnit,
+        rate: u64,
+        start: u64,
+        end: u64,
+        bump: u8,
+        vault_bump: u8,
+    ) -> Result<()> {
+        require!(end > start && rate > 0, DaoError::InvalidPaymentStream);
+        self.proposal = proposal;
+        self.recipient = recipient;
+        self.mint = mint;
+        self.unit = unit;
+        self.rate = rate;
+        self.start = start;
+        self.end = end;
+        self.withdrawn = 0;
+        self.bump = bump;
+        self.vault_bump = vault_bump;
+        // Make sure the total fits before any funds move
+        self.total()?;
+        Ok(())
+    }
+
+    // The current slot or timestamp, in the stream's unit
+    pub fn now(&self) -> Result<u64> {
+        let clock = Clock::get()?;
+        Ok(match self.unit {
+            StreamUnit::Slot => clock.slot,
+            StreamUnit::Second => clock.unix_timestamp.max(0) as u64,
+        })
+    }
+
+    // The total amount the stream pays over its lifetime
+    pub fn total(&self) -> Result<u64> {
+        self.vested_at(self.end)
+    }
+
+    // The amount vested at the given slot or timestamp
+    fn vested_at(&self, now: u64) -> Result<u64> {
+        let elapsed = now.min(self.end).saturating_sub(self.start);
+        Ok(elapsed.checked_mul(self.rate).ok_or(DaoError::Overflow)?)
+    }
+
+    // Records a withdrawal of everything vested so far and returns its amount
+    pub fn withdraw(&mut self) -> Result<u64> {
+        let amount = self
+            .vested_at(self.now()?)?
+            .checked_sub(self.withdrawn)
+            .ok_or(DaoError::Underflow)?;
+        require!(amount > 0, DaoError::NothingToWithdraw);
+        self.withdrawn = self.withdrawn.checked_add(amount).ok_or(DaoError::Overflow)?;
+        Ok(amount)
+    }
+
+    // Stops vesting now and returns the unvested remainder
+    pub fn cancel(&mut self) -> Result<u64> {
+        let total = self.total()?;
+        self.end = self.now()?.clamp(self.start, self.end);
+        Ok(total.checked_sub(self.total()?).ok_or(DaoError::Underflow)?)
+    }
+
+    // The address of the token account escrowing a token stream
+    pub fn vault_address(&self, dao: Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(
+            &[b"stream_vault", dao.as_ref(), self.proposal.as_ref(), &[self.vault_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(DaoError::BumpError))
+    }
+
+    // Ensures a token account is this stream's escrow
+    pub fn check_vault(&self, dao: Pubkey, vault: &Account<TokenAccount>) -> Result<()> {
+        let mint = self.mint.ok_or(DaoError::InvalidStreamAccount)?;
+        require_keys_eq!(vault.key(), self.vault_address(dao)?, DaoError::InvalidStreamAccount);
+        require_keys_eq!(vault.mint, mint, DaoError::InvalidStreamAccount);
+        Ok(())
+    }
+
+    // Whether the stream has ended and been fully withdrawn
+    pub fn is_finished(&self) -> Result<bool> {
+        Ok(self.now()? >= self.end && self.withdrawn >= self.total()?)
+    }
+}
+
+/// Enum representing the clock a payment stream vests against
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StreamUnit {
+    Slot,   // Start, end and rate are in slots
+    Second, // Start, end and rate are in seconds (unix timestamps)
+}
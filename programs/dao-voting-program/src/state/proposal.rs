@@ -1,4 +1,6 @@
 use crate::{constants::*, errors::DaoError};
+
+use super::{FeeRefundPolicy, SplitMode, StreamUnit};
 use anchor_lang::prelude::*;
 
 #[account]
@@ -40,6 +42,21 @@ pub struct Proposal {
     // The total number of 'abstain' votes for the proposal
     pub abstain_votes: u64,
 
+    // How votes are cast (public, or sealed with commit-reveal)
+    pub ballot: BallotMode,
+
+    // Number of sealed votes committed, counted before they're revealed
+    pub commitments: u64,
+
+    // Governance tokens the proposer locked in the bond escrow
+    pub bond: u64,
+
+    // Proposal fee paid into the treasury, in lamports
+    pub fee: u64,
+
+    // Slot at which the proposal was decided, starting the council's veto window. Zero while open.
+    pub decided_at: u64,
+
     // Bump seed for the proposal's PDA
     pub bump: u8,
 }
@@ -49,7 +66,7 @@ pub const PROPOSAL_SPACE: usize =
     U64_LENGTH +  // id: u64
     4 + 32 +  // name: String (4 bytes for length + max 32 characters)
     4 + 72 +  // description: String (4 bytes for length + max 72 characters)
-    ENUM_LENGTH + U64_LENGTH + PUBKEY_LENGTH +  // proposal: ProposalType (1 byte discriminator + largest variant)
+    ENUM_LENGTH + PUBKEY_LENGTH + OPTION_LENGTH + PUBKEY_LENGTH + U64_LENGTH * 3 + ENUM_LENGTH +  // proposal: ProposalType (1 byte discriminator + largest variant)
     ENUM_LENGTH +  // result: ProposalStatus
     U64_LENGTH +  // quorum: u64
     PUBKEY_LENGTH +  // proposer: Pubkey
@@ -58,9 +75,14 @@ pub const PROPOSAL_SPACE: usize =
     U64_LENGTH +  // yes_votes: u64
     U64_LENGTH +  // no_votes: u64
     U64_LENGTH +  // abstain_votes: u64
+    ENUM_LENGTH + U64_LENGTH + BOOL_LENGTH +  // ballot: BallotMode (1 byte discriminator + largest variant)
+    U64_LENGTH +  // commitments: u64
+    U64_LENGTH +  // bond: u64
+    U64_LENGTH +  // fee: u64
+    U64_LENGTH +  // decided_at: u64
     U8_LENGTH;    // bump: u8
 
-// This should total 219 bytes
+// This totals 343 bytes
 
 impl Proposal {
     // Total size of the Proposal account in bytes
@@ -77,6 +99,9 @@ impl Proposal {
     /// * `proposal` - Type of the proposal
     /// * `quorum` - Minimum number of votes required
     /// * `expiry` - Duration in slots until the proposal expires
+    /// * `ballot` - Whether votes are public or sealed with commit-reveal
+    /// * `bond` - Governance tokens locked by the proposer
+    /// * `fee` - Proposal fee paid by the proposer
     /// * `bump` - Bump seed for the proposal's PDA
     ///
     /// # Errors
@@ -91,6 +116,9 @@ impl Proposal {
         proposal: ProposalType,
         quorum: u64,
         expiry: u64,
+        ballot: BallotMode,
+        bond: u64,
+        fee: u64,
         bump: u8,
     ) -> Result<()> {
         require!(name.len() < 33, DaoError::InvalidName);
@@ -106,6 +134,11 @@ impl Proposal {
         self.yes_votes = 0;
         self.no_votes = 0;
         self.abstain_votes = 0;
+        self.ballot = ballot;
+        self.commitments = 0;
+        self.bond = bond;
+        self.fee = fee;
+        self.decided_at = 0;
         self.bump = bump;
         self.expiry = Clock::get()?
             .slot
@@ -114,6 +147,36 @@ impl Proposal {
         Ok(())
     }
 
+    /// Loads a proposal that may have been closed by its execution or cleanup, which ends
+    /// voting on it for good as proposal IDs are never reused
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - The proposal's account
+    /// * `config` - The DAO the proposal must belong to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account exists but isn't one of the DAO's proposals
+    pub fn load<'info>(info: &AccountInfo<'info>, config: Pubkey) -> Result<Option<Account<'info, Proposal>>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        let proposal: Account<'info, Proposal> = Account::try_from(info)?;
+        let address = Pubkey::create_program_address(
+            &[
+                b"proposal",
+                config.as_ref(),
+                proposal.id.to_le_bytes().as_ref(),
+                &[proposal.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| DaoError::InvalidProposalSeed)?;
+        require_keys_eq!(info.key(), address, DaoError::InvalidProposalSeed);
+        Ok(Some(proposal))
+    }
+
     /// Attempts to finalize the proposal based on votes and expiry
     pub fn try_finalize(&mut self) -> Result<()> {
         // First, check if the proposal has already been finalized
@@ -121,6 +184,11 @@ impl Proposal {
             return Ok(()); // Proposal has already been finalized
         }
 
+        // Sealed ballots can't be decided until every voter had a chance to reveal
+        if self.check_reveal_ended().is_err() {
+            return Ok(());
+        }
+
         // Check if the proposal has expired
         let has_expired = self.check_expiry().is_err();
 
@@ -143,6 +211,10 @@ impl Proposal {
             ProposalStatus::Open // Proposal remains open for more votes
         };
 
+        if self.result != ProposalStatus::Open {
+            self.decided_at = Clock::get()?.slot;
+        }
+
         Ok(())
     }
 
@@ -164,6 +236,70 @@ impl Proposal {
         Ok(())
     }
 
+    /// Returns the slot at which the reveal window of a commit-reveal proposal closes
+    pub fn reveal_expiry(&self) -> Result<u64> {
+        match self.ballot {
+            BallotMode::Public => Ok(self.expiry),
+            BallotMode::CommitReveal(reveal_period, _) => Ok(self
+                .expiry
+                .checked_add(reveal_period)
+                .ok_or(DaoError::Overflow)?),
+        }
+    }
+
+    /// Checks that votes on the proposal are cast publicly
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal uses commit-reveal voting
+    pub fn check_public_ballot(&self) -> Result<()> {
+        require!(
+            self.ballot == BallotMode::Public,
+            DaoError::InvalidBallotMode
+        );
+        Ok(())
+    }
+
+    /// Checks that votes on the proposal are sealed with commit-reveal
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal uses public voting
+    pub fn check_sealed_ballot(&self) -> Result<()> {
+        require!(
+            matches!(self.ballot, BallotMode::CommitReveal(..)),
+            DaoError::InvalidBallotMode
+        );
+        Ok(())
+    }
+
+    /// Checks if the proposal is in its reveal window
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if voting is still ongoing or the reveal window has closed
+    pub fn check_reveal_window(&self) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        require!(
+            slot >= self.expiry && slot < self.reveal_expiry()?,
+            DaoError::NotInRevealWindow
+        );
+        Ok(())
+    }
+
+    /// Checks if the reveal window, if any, has closed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current slot is before the end of the reveal window
+    pub fn check_reveal_ended(&self) -> Result<()> {
+        require!(
+            Clock::get()?.slot >= self.reveal_expiry()? || self.ballot == BallotMode::Public,
+            DaoError::NotInRevealWindow
+        );
+        Ok(())
+    }
+
     /// Checks if the proposal is still open
     ///
     /// # Errors
@@ -203,6 +339,163 @@ impl Proposal {
         Ok(())
     }
 
+    /// Checks if the proposal was rejected, either by failing or being vetoed by the council
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal is not in the Failed or Vetoed status
+    pub fn is_rejected(&self) -> Result<()> {
+        require!(
+            matches!(self.result, ProposalStatus::Failed | ProposalStatus::Vetoed),
+            DaoError::InvalidProposalStatus
+        );
+        Ok(())
+    }
+
+    /// Marks a succeeded proposal as vetoed by the council, blocking its execution
+    ///
+    /// # Arguments
+    ///
+    /// * `veto_window` - Slots after the proposal was decided during which it can be vetoed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal hasn't succeeded or the veto window has closed
+    pub fn veto(&mut self, veto_window: u64) -> Result<()> {
+        self.is_succeeded()?;
+        require!(
+            Clock::get()?.slot < self.veto_deadline(veto_window)?,
+            DaoError::VetoWindowClosed
+        );
+        self.result = ProposalStatus::Vetoed;
+        Ok(())
+    }
+
+    /// Checks that the council's veto window has closed, so the proposal can be executed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal was decided less than `veto_window` slots ago
+    pub fn check_veto_window_closed(&self, veto_window: u64) -> Result<()> {
+        require!(
+            Clock::get()?.slot >= self.veto_deadline(veto_window)?,
+            DaoError::VetoWindowOpen
+        );
+        Ok(())
+    }
+
+    fn veto_deadline(&self, veto_window: u64) -> Result<u64> {
+        Ok(self
+            .decided_at
+            .checked_add(veto_window)
+            .ok_or(DaoError::Overflow)?)
+    }
+
+    /// Checks whether the proposer's bond should be slashed rather than returned.
+    /// Bonds are returned when the proposal succeeds, or fails with enough participation
+    /// and a No share below `slash_bps`. Vetoed proposals always lose their bond.
+    ///
+    /// # Arguments
+    ///
+    /// * `slash_bps` - Share of No votes, in basis points, at or above which the bond is slashed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal hasn't been decided yet
+    pub fn is_bond_slashed(&self, slash_bps: u16) -> Result<bool> {
+        match self.result {
+            ProposalStatus::Succeeded => Ok(false),
+            ProposalStatus::Failed => {
+                let total_votes = self
+                    .yes_votes
+                    .checked_add(self.no_votes)
+                    .ok_or(DaoError::Overflow)?;
+                if total_votes < self.quorum {
+                    return Ok(true); // Not enough participation
+                }
+                let no_share = (self.no_votes as u128)
+                    .checked_mul(BASIS_POINTS as u128)
+                    .ok_or(DaoError::Overflow)?
+                    / (total_votes as u128).max(1);
+                Ok(no_share >= slash_bps as u128)
+            }
+            ProposalStatus::Vetoed => Ok(true),
+            ProposalStatus::Open => err!(DaoError::InvalidProposalStatus),
+        }
+    }
+
+    /// Returns the part of the proposal fee owed back to the proposer
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The DAO's fee refund policy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proposal hasn't been decided yet
+    pub fn fee_refund(&self, policy: FeeRefundPolicy) -> Result<u64> {
+        let quorum_met = self
+            .yes_votes
+            .checked_add(self.no_votes)
+            .ok_or(DaoError::Overflow)?
+            >= self.quorum;
+        match (self.result, policy) {
+            (ProposalStatus::Open, _) => err!(DaoError::InvalidProposalStatus),
+            (ProposalStatus::Vetoed, _) => Ok(0),
+            (_, FeeRefundPolicy::Keep) => Ok(0),
+            (ProposalStatus::Succeeded, _) => Ok(self.fee),
+            (ProposalStatus::Failed, FeeRefundPolicy::RefundOnQuorum(refund_bps)) if quorum_met => {
+                let refund = (self.fee as u128)
+                    .checked_mul(refund_bps as u128)
+                    .ok_or(DaoError::Overflow)?
+                    / BASIS_POINTS as u128;
+                Ok(refund as u64)
+            }
+            (ProposalStatus::Failed, _) => Ok(0),
+        }
+    }
+
+    /// Returns the share of Yes votes among Yes and No votes, in basis points
+    pub fn yes_share_bps(&self) -> Result<u64> {
+        let total_votes = self
+            .yes_votes
+            .checked_add(self.no_votes)
+            .ok_or(DaoError::Overflow)?;
+        if total_votes == 0 {
+            return Ok(0);
+        }
+        Ok(((self.yes_votes as u128)
+            .checked_mul(BASIS_POINTS as u128)
+            .ok_or(DaoError::Overflow)?
+            / total_votes as u128) as u64)
+    }
+
+    /// Counts a sealed vote committed to the proposal
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the count overflows
+    pub fn add_commitment(&mut self) -> Result<()> {
+        self.commitments = self
+            .commitments
+            .checked_add(1)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    /// Ensures no vote has been cast or committed yet
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if anyone has voted or committed a sealed vote
+    pub fn check_no_votes(&self) -> Result<()> {
+        require!(
+            self.votes == 0 && self.commitments == 0,
+            DaoError::VotingStarted
+        );
+        Ok(())
+    }
+
     /// Adds votes to the proposal
     ///
     /// # Arguments
@@ -291,6 +584,80 @@ pub enum ProposalType {
     Bounty(Pubkey, u64), // Pay an address some amount of SOL
     Executable,          // Sign some kind of instruction(s) with an accounts struct, etc
     Vote,                // We just want to know what people think. No money involved
+    FundRewards(u64),    // Move some amount of SOL from the treasury to the stakers' reward pool
+    TokenBounty(Pubkey, Pubkey, u64), // Pay an address some amount of an SPL token (mint, payee, amount) from the treasury's vault
+    MilestoneBounty(Pubkey, Pubkey, u64, u8), // Escrow SOL for an address, released in tranches (payee, reviewer, total, tranches)
+    ReleaseMilestone(Pubkey), // Confirm vote releasing the next tranche of a milestone bounty
+    AbandonMilestone(Pubkey), // Return a milestone bounty's unreleased SOL to the treasury
+    PaymentStream(Pubkey, Option<Pubkey>, u64, u64, u64, StreamUnit), // Stream SOL or a token to an address (recipient, mint, rate, start, end, unit)
+    CancelStream(Pubkey), // Stop a payment stream and return its unvested remainder to the treasury
+    SplitBounty(u64, SplitMode), // Pay some amount of SOL to the recipients recorded in the proposal's BountySplit
+    FundSubTreasury(Pubkey, u64, u64), // Give a sub-treasury a new budget for a number of slots (sub-treasury, budget, period)
+    SetGuardian(Option<Pubkey>), // Replace or remove the DAO's guardian
+    SetRoles(Pubkey, u8), // Replace a member's role bitmask (member, roles)
+    CreateSubTreasury, // Create the unfunded sub-treasury recorded in the proposal's StewardSet
+    SetStewards(Pubkey), // Replace a sub-treasury's stewards with those recorded in the proposal's StewardSet
+}
+
+impl ProposalType {
+    /// Returns the SOL the proposal takes from the treasury when executed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stream's total overflows
+    pub fn treasury_spend(&self) -> Result<u64> {
+        Ok(match *self {
+            ProposalType::Bounty(_, amount)
+            | ProposalType::FundRewards(amount)
+            | ProposalType::MilestoneBounty(_, _, amount, _)
+            | ProposalType::SplitBounty(amount, _)
+            | ProposalType::FundSubTreasury(_, amount, _) => amount,
+            ProposalType::PaymentStream(_, None, rate, start, end, _) => end
+                .saturating_sub(start)
+                .checked_mul(rate)
+                .ok_or(DaoError::Overflow)?,
+            _ => 0,
+        })
+    }
+
+    /// Returns the mint and amount of tokens the proposal takes from a treasury vault when
+    /// executed, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a stream's total overflows
+    pub fn treasury_token_spend(&self) -> Result<Option<(Pubkey, u64)>> {
+        Ok(match *self {
+            ProposalType::TokenBounty(mint, _, amount) => Some((mint, amount)),
+            ProposalType::PaymentStream(_, Some(mint), rate, start, end, _) => Some((
+                mint,
+                end.saturating_sub(start)
+                    .checked_mul(rate)
+                    .ok_or(DaoError::Overflow)?,
+            )),
+            _ => None,
+        })
+    }
+
+    // Whether executing the proposal can move funds out of the treasury, and so is
+    // blocked while payouts are paused
+    pub fn pays_out(&self) -> bool {
+        !matches!(
+            self,
+            ProposalType::Vote
+                | ProposalType::SetGuardian(_)
+                | ProposalType::SetRoles(..)
+                | ProposalType::CreateSubTreasury
+                | ProposalType::SetStewards(_)
+        )
+    }
+}
+
+/// Enum representing how votes are cast on a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BallotMode {
+    Public,                  // Votes are tallied as soon as they're cast
+    CommitReveal(u64, bool), // Votes are sealed, then revealed over a number of slots after expiry, optionally penalizing unrevealed commits
 }
 
 /// Enum representing the current status of a proposal
@@ -299,6 +666,7 @@ pub enum ProposalStatus {
     Open,      // The proposal is active and accepting votes
     Succeeded, // The proposal has passed (met quorum and not expired)
     Failed,    // The proposal has failed (didn't meet quorum or expired)
+    Vetoed,    // The proposal passed but was blocked by the council
 }
 
 /// Enum representing the type of vote
@@ -0,0 +1,88 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+
+// The RewardPool account holds SOL distributed from the treasury to stakers.
+// Rewards are tracked with a reward-per-token accumulator, so each stake only
+// earns from distributions made while its tokens were staked.
+#[account]
+pub struct RewardPool {
+    // The total amount of tokens staked across all members
+    pub total_staked: u64,
+
+    // Rewards accumulated per staked token, scaled by REWARD_PRECISION
+    pub reward_per_token: u128,
+
+    // The total amount of lamports distributed to stakers
+    pub total_distributed: u64,
+
+    // The total amount of lamports claimed by stakers
+    pub total_claimed: u64,
+
+    // Bump seed for the reward pool's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl RewardPool {
+    /// Total size of the RewardPool account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        U64_LENGTH +       // total_staked: u64
+        U128_LENGTH +      // reward_per_token: u128
+        U64_LENGTH +       // total_distributed: u64
+        U64_LENGTH +       // total_claimed: u64
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new RewardPool account
+    pub fn init(&mut self, bump: u8) -> Result<()> {
+        self.total_staked = 0;
+        self.reward_per_token = 0;
+        self.total_distributed = 0;
+        self.total_claimed = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Tracks tokens entering the pool
+    pub fn stake(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self
+            .total_staked
+            .checked_add(amount)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    // Tracks tokens leaving the pool
+    pub fn unstake(&mut self, amount: u64) -> Result<()> {
+        self.total_staked = self
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(DaoError::Underflow)?;
+        Ok(())
+    }
+
+    // Spreads newly funded lamports over everything currently staked
+    pub fn distribute(&mut self, amount: u64) -> Result<()> {
+        require!(self.total_staked > 0, DaoError::NoStakers);
+        let increase = (amount as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(DaoError::Overflow)?
+            / self.total_staked as u128;
+        self.reward_per_token = self
+            .reward_per_token
+            .checked_add(increase)
+            .ok_or(DaoError::Overflow)?;
+        self.total_distributed = self
+            .total_distributed
+            .checked_add(amount)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    // Records lamports paid out to a staker
+    pub fn claim(&mut self, amount: u64) -> Result<()> {
+        self.total_claimed = self
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+}
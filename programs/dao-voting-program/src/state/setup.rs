@@ -1,5 +1,8 @@
 use crate::{constants::*, errors::DaoError};
+
+use super::{StakeState, VoterWeightAction, VoterWeightRecord};
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
 
 #[account]
 pub struct DaoSetup {
@@ -27,6 +30,96 @@ pub struct DaoSetup {
     // Counter keeping track of the total number of proposals submitted
     pub proposal_count: u64,
 
+    // Trusted voter-weight plugin program, replacing raw stake as the source of voting weight
+    pub voter_weight_plugin: Option<Pubkey>,
+
+    // Verified NFT collection whose holders govern the DAO instead of stakers
+    pub nft_collection: Option<Pubkey>,
+
+    // Voting weight of each NFT in the collection
+    pub nft_vote_weight: u64,
+
+    // Key that records per-NFT weights earned by the collection's traits, which live off-chain.
+    // NFTs it hasn't weighed, or every NFT without one, count nft_vote_weight.
+    pub nft_weight_authority: Option<Pubkey>,
+
+    // Extra weight given to reputation, in basis points: votes count
+    // amount * (1 + coefficient * min(reputation, MAX) / MAX). Zero disables hybrid voting.
+    pub reputation_coefficient_bps: u16,
+
+    // Whether staking mints transferable receipt tokens, which carry the voting power of the
+    // tokens staked for them
+    pub liquid_staking: bool,
+
+    // Longest lock, in slots, a stake position can be given. Zero disables locks.
+    pub max_lock_period: u64,
+
+    // Governance tokens a proposer locks while their proposal is open. Zero disables bonds.
+    pub proposal_bond: u64,
+
+    // Share of No votes, in basis points, at or above which a failed proposal's bond is slashed
+    pub bond_slash_bps: u16,
+
+    // Whether and how much of the proposal fee is refunded once a proposal is settled
+    pub fee_refund_policy: FeeRefundPolicy,
+
+    // Most SOL a single proposal can take from the treasury. Zero means no limit.
+    pub max_spend_per_proposal: u64,
+
+    // Most SOL proposals can take from the treasury in one epoch. Zero means no limit.
+    pub max_spend_per_epoch: u64,
+
+    // Most a single proposal can take from a treasury token vault, in basis points of the
+    // vault's balance. Zero means no limit.
+    pub max_token_spend_per_proposal_bps: u16,
+
+    // Most proposals can take from a treasury token vault in one epoch, in basis points of
+    // the vault's balance at the epoch's first payout. Zero means no limit.
+    pub max_token_spend_per_epoch_bps: u16,
+
+    // Share of the treasury balance, in basis points, a payout must leave behind. Applies
+    // to SOL and to each token vault.
+    pub reserve_floor_bps: u16,
+
+    // Share of Yes votes, in basis points, at or above which a proposal bypasses the
+    // spending limits. Zero disables the emergency override.
+    pub override_threshold_bps: u16,
+
+    // The epoch epoch_spent is tracked for
+    pub spend_epoch: u64,
+
+    // SOL taken from the treasury by proposals during spend_epoch
+    pub epoch_spent: u64,
+
+    // Key allowed to temporarily pause voting, proposal creation and treasury payouts
+    pub guardian: Option<Pubkey>,
+
+    // Slot until which voting is paused
+    pub voting_paused_until: u64,
+
+    // Slot until which proposal creation is paused
+    pub proposals_paused_until: u64,
+
+    // Slot until which treasury payouts are paused
+    pub payouts_paused_until: u64,
+
+    // Keys that can veto succeeded proposals
+    pub council: Vec<Pubkey>,
+
+    // Mint whose holders can veto succeeded proposals, instead of a list of keys
+    pub council_mint: Option<Pubkey>,
+
+    // Slots after a proposal succeeds during which the council can veto it, delaying execution
+    pub veto_window: u64,
+
+    // Proposal fees held in the treasury that may still be refunded to their proposers,
+    // so they aren't paid out to members who ragequit
+    pub escrowed_fees: u64,
+
+    // Number of member states ever opened, used to give each membership its own range of
+    // signed vote nonces so messages signed before an exit can't be replayed after a rejoin
+    pub memberships: u64,
+
     // Bump seed for the authority PDA
     pub auth_bump: u8,
 
@@ -38,6 +131,9 @@ pub struct DaoSetup {
 
     // Bump seed for the treasury account PDA
     pub treasury_bump: u8,
+
+    // Bump seed for the staking receipt mint PDA, 0 without liquid staking
+    pub receipt_mint_bump: u8,
 }
 
 impl DaoSetup {
@@ -51,40 +147,121 @@ impl DaoSetup {
         U64_LENGTH +       // min_quorum: u64
         U64_LENGTH +       // max_expiry: u64
         U64_LENGTH +       // proposal_count: u64
+        OPTION_LENGTH + PUBKEY_LENGTH + // voter_weight_plugin: Option<Pubkey>
+        OPTION_LENGTH + PUBKEY_LENGTH + // nft_collection: Option<Pubkey>
+        U64_LENGTH +       // nft_vote_weight: u64
+        OPTION_LENGTH + PUBKEY_LENGTH + // nft_weight_authority: Option<Pubkey>
+        U16_LENGTH +       // reputation_coefficient_bps: u16
+        BOOL_LENGTH +      // liquid_staking: bool
+        U64_LENGTH +       // max_lock_period: u64
+        U64_LENGTH +       // proposal_bond: u64
+        U16_LENGTH +       // bond_slash_bps: u16
+        ENUM_LENGTH + U16_LENGTH + // fee_refund_policy: FeeRefundPolicy (1 byte discriminator + largest variant)
+        U64_LENGTH +       // max_spend_per_proposal: u64
+        U64_LENGTH +       // max_spend_per_epoch: u64
+        U16_LENGTH +       // max_token_spend_per_proposal_bps: u16
+        U16_LENGTH +       // max_token_spend_per_epoch_bps: u16
+        U16_LENGTH +       // reserve_floor_bps: u16
+        U16_LENGTH +       // override_threshold_bps: u16
+        U64_LENGTH +       // spend_epoch: u64
+        U64_LENGTH +       // epoch_spent: u64
+        OPTION_LENGTH + PUBKEY_LENGTH + // guardian: Option<Pubkey>
+        U64_LENGTH +       // voting_paused_until: u64
+        U64_LENGTH +       // proposals_paused_until: u64
+        U64_LENGTH +       // payouts_paused_until: u64
+        4 + MAX_COUNCIL_MEMBERS * PUBKEY_LENGTH + // council: Vec<Pubkey>
+        OPTION_LENGTH + PUBKEY_LENGTH + // council_mint: Option<Pubkey>
+        U64_LENGTH +       // veto_window: u64
+        U64_LENGTH +       // escrowed_fees: u64
+        U64_LENGTH +       // memberships: u64
         U8_LENGTH +        // auth_bump: u8
         U8_LENGTH +        // config_bump: u8
         U8_LENGTH +        // mint_bump: u8
-        U8_LENGTH; // treasury_bump: u8
+        U8_LENGTH +        // treasury_bump: u8
+        U8_LENGTH; // receipt_mint_bump: u8
 
     /// Initializes a new DaoConfig instance with the provided parameters
     ///
     /// # Arguments
     ///
     /// * `seed` - Unique identifier for this DAO configuration
-    /// * `issue_price` - Price at which tokens are issued
-    /// * `issue_amount` - Quantity of tokens issued per transaction
-    /// * `proposal_fee` - Fee for submitting a proposal
-    /// * `max_supply` - Maximum token supply for the DAO
-    /// * `min_quorum` - Minimum votes required for a valid proposal
-    /// * `max_expiry` - Maximum duration for an active proposal
+    /// * `settings` - The DAO's issuance, governance and treasury settings
     /// * `auth_bump` - Bump seed for authority PDA
     /// * `config_bump` - Bump seed for configuration PDA
     /// * `mint_bump` - Bump seed for token mint PDA
     /// * `treasury_bump` - Bump seed for treasury PDA
+    /// * `receipt_mint_bump` - Bump seed for staking receipt mint PDA, 0 without liquid staking
     pub fn init(
         &mut self,
         seed: u64,
-        issue_price: u64,
-        issue_amount: u64,
-        proposal_fee: u64,
-        max_supply: u64,
-        min_quorum: u64,
-        max_expiry: u64,
+        settings: DaoSettings,
         auth_bump: u8,
         config_bump: u8,
         mint_bump: u8,
         treasury_bump: u8,
+        receipt_mint_bump: u8,
     ) -> Result<()> {
+        let DaoSettings {
+            issue_price,
+            issue_amount,
+            proposal_fee,
+            max_supply,
+            min_quorum,
+            max_expiry,
+            voter_weight_plugin,
+            nft_collection,
+            nft_vote_weight,
+            nft_weight_authority,
+            reputation_coefficient_bps,
+            liquid_staking,
+            max_lock_period,
+            proposal_bond,
+            bond_slash_bps,
+            fee_refund_policy,
+            max_spend_per_proposal,
+            max_spend_per_epoch,
+            max_token_spend_per_proposal_bps,
+            max_token_spend_per_epoch_bps,
+            reserve_floor_bps,
+            override_threshold_bps,
+            guardian,
+            council,
+            council_mint,
+            veto_window,
+        } = settings;
+
+        // Voting weight comes from a plugin, NFTs or stake, never a mix
+        require!(
+            voter_weight_plugin.is_none() || nft_collection.is_none(),
+            DaoError::InvalidGovernanceMode
+        );
+        if nft_collection.is_some() {
+            require!(nft_vote_weight > 0, DaoError::InvalidGovernanceMode);
+        } else {
+            require!(nft_weight_authority.is_none(), DaoError::InvalidGovernanceMode);
+        }
+        require!(bond_slash_bps as u64 <= BASIS_POINTS, DaoError::InvalidBondConfig);
+        if let FeeRefundPolicy::RefundOnQuorum(refund_bps) = fee_refund_policy {
+            require!(refund_bps as u64 <= BASIS_POINTS, DaoError::InvalidFeeRefundPolicy);
+        }
+        // The override needs a supermajority, more than a simple majority
+        require!(
+            reserve_floor_bps as u64 <= BASIS_POINTS
+                && max_token_spend_per_proposal_bps as u64 <= BASIS_POINTS
+                && max_token_spend_per_epoch_bps as u64 <= BASIS_POINTS
+                && (override_threshold_bps == 0
+                    || (override_threshold_bps as u64 > BASIS_POINTS / 2
+                        && override_threshold_bps as u64 <= BASIS_POINTS)),
+            DaoError::InvalidSpendingLimits
+        );
+        // The council is a list of keys or a token mint, and needs time to veto
+        require!(
+            council.len() <= MAX_COUNCIL_MEMBERS && (council.is_empty() || council_mint.is_none()),
+            DaoError::InvalidCouncil
+        );
+        if !council.is_empty() || council_mint.is_some() {
+            require!(veto_window > 0, DaoError::InvalidCouncil);
+        }
         self.seed = seed;
         self.issue_price = issue_price;
         self.issue_amount = issue_amount;
@@ -93,10 +270,38 @@ impl DaoSetup {
         self.min_quorum = min_quorum;
         self.max_expiry = max_expiry;
         self.proposal_count = 0;
+        self.voter_weight_plugin = voter_weight_plugin;
+        self.nft_collection = nft_collection;
+        self.nft_vote_weight = nft_vote_weight;
+        self.nft_weight_authority = nft_weight_authority;
+        self.reputation_coefficient_bps = reputation_coefficient_bps;
+        self.liquid_staking = liquid_staking;
+        self.max_lock_period = max_lock_period;
+        self.proposal_bond = proposal_bond;
+        self.bond_slash_bps = bond_slash_bps;
+        self.fee_refund_policy = fee_refund_policy;
+        self.max_spend_per_proposal = max_spend_per_proposal;
+        self.max_spend_per_epoch = max_spend_per_epoch;
+        self.max_token_spend_per_proposal_bps = max_token_spend_per_proposal_bps;
+        self.max_token_spend_per_epoch_bps = max_token_spend_per_epoch_bps;
+        self.reserve_floor_bps = reserve_floor_bps;
+        self.override_threshold_bps = override_threshold_bps;
+        self.spend_epoch = 0;
+        self.epoch_spent = 0;
+        self.guardian = guardian;
+        self.voting_paused_until = 0;
+        self.proposals_paused_until = 0;
+        self.payouts_paused_until = 0;
+        self.council = council;
+        self.council_mint = council_mint;
+        self.veto_window = veto_window;
+        self.escrowed_fees = 0;
+        self.memberships = 0;
         self.auth_bump = auth_bump;
         self.config_bump = config_bump;
         self.mint_bump = mint_bump;
         self.treasury_bump = treasury_bump;
+        self.receipt_mint_bump = receipt_mint_bump;
         Ok(())
     }
 
@@ -132,6 +337,361 @@ impl DaoSetup {
         Ok(())
     }
 
+    /// Returns the voting weight of a staker for an action
+    ///
+    /// # Arguments
+    ///
+    /// * `dao` - The key of this configuration account
+    /// * `stake_state` - The staker's stake state
+    /// * `voter_weight_record` - The plugin's voter weight record, required when a plugin is configured
+    /// * `action` - The action the weight is used for
+    /// * `target` - The account the action targets, if any
+    /// * `receipt_account` - The voter's receipt token account, required with liquid staking
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a plugin is configured and the record is missing or doesn't apply,
+    /// if liquid staking is enabled and the receipt account is missing or invalid,
+    /// or if the DAO is governed by an NFT collection
+    pub fn voting_power(
+        &self,
+        dao: Pubkey,
+        stake_state: &StakeState,
+        voter_weight_record: Option<&AccountInfo>,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+        receipt_account: Option<&TokenAccount>,
+    ) -> Result<u64> {
+        // NFT DAOs vote through nft_vote, never with stake
+        require!(self.nft_collection.is_none(), DaoError::InvalidGovernanceMode);
+        match self.voter_weight_plugin {
+            Some(plugin) => {
+                let record = voter_weight_record.ok_or(DaoError::InvalidVoterWeightRecord)?;
+                VoterWeightRecord::load(record, &plugin)?.check_weight(
+                    dao,
+                    stake_state.owner,
+                    action,
+                    target,
+                )
+            }
+            // Voting power follows the receipts, each backed by one staked token, so whoever
+            // holds them votes with them. Holders without stake vote from an empty position.
+            None if self.liquid_staking => {
+                let receipt_account = receipt_account.ok_or(DaoError::InvalidReceiptAccount)?;
+                self.check_receipt_account(dao, receipt_account, stake_state.owner)?;
+                Ok(receipt_account.amount)
+            }
+            None => Ok(stake_state.amount),
+        }
+    }
+
+    /// Returns the address of the staking receipt mint
+    ///
+    /// # Arguments
+    ///
+    /// * `dao` - The key of this configuration account
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored bump doesn't derive a valid address
+    pub fn receipt_mint(&self, dao: Pubkey) -> Result<Pubkey> {
+        Pubkey::create_program_address(
+            &[b"receipt", dao.as_ref(), &[self.receipt_mint_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(DaoError::BumpError))
+    }
+
+    /// Verifies that a token account holds this DAO's staking receipts for the given owner
+    ///
+    /// # Arguments
+    ///
+    /// * `dao` - The key of this configuration account
+    /// * `receipt_account` - The token account to check
+    /// * `owner` - The expected owner of the token account
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account's mint or owner don't match
+    pub fn check_receipt_account(
+        &self,
+        dao: Pubkey,
+        receipt_account: &TokenAccount,
+        owner: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(receipt_account.mint, self.receipt_mint(dao)?, DaoError::InvalidReceiptAccount);
+        require_keys_eq!(receipt_account.owner, owner, DaoError::InvalidReceiptAccount);
+        Ok(())
+    }
+
+    /// Returns the weight a vote adds to the tally, boosted by the voter's reputation
+    /// when hybrid voting is enabled
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount of votes cast
+    /// * `reputation` - The voter's reputation score
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the weight overflows
+    pub fn vote_weight(&self, amount: u64, reputation: u64) -> Result<u64> {
+        let bonus = (amount as u128)
+            .checked_mul(self.reputation_coefficient_bps as u128)
+            .and_then(|x| x.checked_mul(reputation.min(MAX_REPUTATION_SCORE) as u128))
+            .and_then(|x| x.checked_div((BASIS_POINTS * MAX_REPUTATION_SCORE) as u128))
+            .ok_or(DaoError::Overflow)?;
+        let weight = (amount as u128)
+            .checked_add(bonus)
+            .ok_or(DaoError::Overflow)?;
+        Ok(u64::try_from(weight).map_err(|_| DaoError::Overflow)?)
+    }
+
+    /// Records SOL a proposal takes from the treasury, enforcing the spending limits
+    /// unless the proposal passed with the emergency override's supermajority
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The lamports leaving the treasury
+    /// * `treasury_balance` - The treasury's balance before the payout
+    /// * `yes_share_bps` - The proposal's share of Yes votes, in basis points
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a limit or the reserve floor would be exceeded
+    pub fn record_spend(&mut self, amount: u64, treasury_balance: u64, yes_share_bps: u64) -> Result<()> {
+        // Start a new tally each epoch
+        let epoch = Clock::get()?.epoch;
+        if epoch != self.spend_epoch {
+            self.spend_epoch = epoch;
+            self.epoch_spent = 0;
+        }
+        let epoch_spent = self
+            .epoch_spent
+            .checked_add(amount)
+            .ok_or(DaoError::Overflow)?;
+
+        if !self.is_spend_overridden(yes_share_bps) {
+            require!(
+                self.max_spend_per_proposal == 0 || amount <= self.max_spend_per_proposal,
+                DaoError::SpendingLimitExceeded
+            );
+            require!(
+                self.max_spend_per_epoch == 0 || epoch_spent <= self.max_spend_per_epoch,
+                DaoError::SpendingLimitExceeded
+            );
+            let reserve = (treasury_balance as u128)
+                .checked_mul(self.reserve_floor_bps as u128)
+                .ok_or(DaoError::Overflow)?
+                / BASIS_POINTS as u128;
+            require!(
+                amount as u128 <= treasury_balance as u128 - reserve,
+                DaoError::ReserveFloorBreached
+            );
+        }
+
+        self.epoch_spent = epoch_spent;
+        Ok(())
+    }
+
+    /// Returns whether a proposal's share of Yes votes reaches the emergency override,
+    /// letting it bypass the spending limits and reserve floor
+    ///
+    /// # Arguments
+    ///
+    /// * `yes_share_bps` - The proposal's share of Yes votes, in basis points
+    pub fn is_spend_overridden(&self, yes_share_bps: u64) -> bool {
+        self.override_threshold_bps > 0 && yes_share_bps >= self.override_threshold_bps as u64
+    }
+
+    /// Ensures the signer is the DAO's NFT weight authority
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no weight authority or the key isn't the weight authority
+    pub fn check_nft_weight_authority(&self, key: Pubkey) -> Result<()> {
+        require!(
+            self.nft_weight_authority == Some(key),
+            DaoError::InvalidNftWeightAuthority
+        );
+        Ok(())
+    }
+
+    /// Ensures the signer is the DAO's guardian
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no guardian or the key isn't the guardian
+    pub fn check_guardian(&self, key: Pubkey) -> Result<()> {
+        require!(self.guardian == Some(key), DaoError::InvalidGuardian);
+        Ok(())
+    }
+
+    /// Pauses part of the DAO for `duration` slots
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - What to pause
+    /// * `duration` - Length of the pause in slots, at most MAX_PAUSE_DURATION
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the duration is invalid, the target is already paused, or its
+    /// last pause ended less than PAUSE_COOLDOWN slots ago. The cooldown stops a guardian from
+    /// chaining pauses, leaving governance time to replace them.
+    pub fn pause(&mut self, target: PauseTarget, duration: u64) -> Result<()> {
+        require!(
+            duration > 0 && duration <= MAX_PAUSE_DURATION,
+            DaoError::InvalidPauseDuration
+        );
+        let slot = Clock::get()?.slot;
+        let paused_until = self.paused_until(target);
+        require!(
+            *paused_until == 0 || slot >= paused_until.saturating_add(PAUSE_COOLDOWN),
+            DaoError::PauseCooldown
+        );
+        *paused_until = slot.checked_add(duration).ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    /// Lifts a pause early. The cooldown starts from now.
+    pub fn unpause(&mut self, target: PauseTarget) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let paused_until = self.paused_until(target);
+        require!(slot < *paused_until, DaoError::NotPaused);
+        *paused_until = slot;
+        Ok(())
+    }
+
+    /// Ensures the given part of the DAO isn't paused
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pause on the target hasn't expired yet
+    pub fn check_not_paused(&self, target: PauseTarget) -> Result<()> {
+        let paused_until = match target {
+            PauseTarget::Voting => self.voting_paused_until,
+            PauseTarget::ProposalCreation => self.proposals_paused_until,
+            PauseTarget::Payouts => self.payouts_paused_until,
+        };
+        require!(Clock::get()?.slot >= paused_until, DaoError::DaoPaused);
+        Ok(())
+    }
+
+    fn paused_until(&mut self, target: PauseTarget) -> &mut u64 {
+        match target {
+            PauseTarget::Voting => &mut self.voting_paused_until,
+            PauseTarget::ProposalCreation => &mut self.proposals_paused_until,
+            PauseTarget::Payouts => &mut self.payouts_paused_until,
+        }
+    }
+
+    /// Counts a new member state and returns the first nonce of its range, so votes signed
+    /// during an earlier membership of the same key can't be replayed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the count or the nonce overflows
+    pub fn next_membership_nonce(&mut self) -> Result<u64> {
+        self.memberships = self
+            .memberships
+            .checked_add(1)
+            .ok_or(DaoError::Overflow)?;
+        self.memberships
+            .checked_mul(NONCES_PER_MEMBERSHIP)
+            .ok_or(DaoError::Overflow.into())
+    }
+
+    /// Holds back a proposal fee that may be refunded once the proposal is settled
+    ///
+    /// # Arguments
+    ///
+    /// * `fee` - The fee paid into the treasury
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the escrowed total overflows
+    pub fn escrow_fee(&mut self, fee: u64) -> Result<()> {
+        if self.fee_refund_policy == FeeRefundPolicy::Keep {
+            return Ok(());
+        }
+        self.escrowed_fees = self
+            .escrowed_fees
+            .checked_add(fee)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    /// Releases a settled proposal's fee, whether it was refunded or kept by the treasury
+    ///
+    /// # Arguments
+    ///
+    /// * `fee` - The fee the proposal paid into the treasury
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if more is released than was escrowed
+    pub fn release_fee(&mut self, fee: u64) -> Result<()> {
+        if self.fee_refund_policy == FeeRefundPolicy::Keep {
+            return Ok(());
+        }
+        self.escrowed_fees = self
+            .escrowed_fees
+            .checked_sub(fee)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    /// Whether the DAO has a council able to veto proposals
+    pub fn has_council(&self) -> bool {
+        !self.council.is_empty() || self.council_mint.is_some()
+    }
+
+    /// Ensures the key belongs to the council, either by being listed, holding council tokens
+    /// or having been given the council role
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to check
+    /// * `council_token_account` - The key's council token account, when the council is a mint
+    /// * `has_council_role` - Whether the key's member state has the council role
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key isn't on the council
+    pub fn check_council_member(
+        &self,
+        key: Pubkey,
+        council_token_account: Option<&TokenAccount>,
+        has_council_role: bool,
+    ) -> Result<()> {
+        if self.council.contains(&key) || has_council_role {
+            return Ok(());
+        }
+        let is_holder = match (self.council_mint, council_token_account) {
+            (Some(mint), Some(account)) => {
+                account.mint == mint && account.owner == key && account.amount > 0
+            }
+            _ => false,
+        };
+        require!(is_holder, DaoError::InvalidCouncilMember);
+        Ok(())
+    }
+
+    /// Verifies that a stake lock doesn't exceed the maximum allowed
+    ///
+    /// # Arguments
+    ///
+    /// * `lock_period` - The lock to be checked, in slots
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock is longer than max_lock_period
+    pub fn check_lock_period(&self, lock_period: u64) -> Result<()> {
+        require!(lock_period <= self.max_lock_period, DaoError::LockPeriodTooLong);
+        Ok(())
+    }
+
     /// Verifies that the given expiry time doesn't exceed the maximum allowed
     ///
     /// # Arguments
@@ -146,3 +706,102 @@ impl DaoSetup {
         Ok(())
     }
 }
+
+/// Enum representing how the proposal fee is handled once a proposal is settled
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FeeRefundPolicy {
+    Keep,                // The fee always stays in the treasury
+    RefundOnSuccess,     // The fee is refunded when the proposal succeeds
+    RefundOnQuorum(u16), // Refunded on success, and this share (in basis points) refunded when a failed proposal met quorum
+}
+
+/// Enum representing the parts of the DAO the guardian can pause
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PauseTarget {
+    Voting,           // Casting, committing and revealing votes
+    ProposalCreation, // Creating proposals
+    Payouts,          // Executing proposals and other payouts from the treasury
+}
+
+/// Settings a DAO is initialized with, passed to `initialize` as a single argument so
+/// adding a setting doesn't grow the instruction's argument list
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DaoSettings {
+    // Price at which tokens are issued
+    pub issue_price: u64,
+
+    // Quantity of tokens issued per transaction
+    pub issue_amount: u64,
+
+    // Fee for submitting a proposal
+    pub proposal_fee: u64,
+
+    // Maximum token supply for the DAO
+    pub max_supply: u64,
+
+    // Minimum votes required for a valid proposal
+    pub min_quorum: u64,
+
+    // Maximum duration for an active proposal
+    pub max_expiry: u64,
+
+    // Optional plugin program providing voter weights
+    pub voter_weight_plugin: Option<Pubkey>,
+
+    // Optional NFT collection whose holders vote instead of stakers
+    pub nft_collection: Option<Pubkey>,
+
+    // Voting weight of each NFT in the collection
+    pub nft_vote_weight: u64,
+
+    // Optional key that weighs NFTs by their traits
+    pub nft_weight_authority: Option<Pubkey>,
+
+    // Extra vote weight from reputation, in basis points
+    pub reputation_coefficient_bps: u16,
+
+    // Whether staking mints transferable receipt tokens
+    pub liquid_staking: bool,
+
+    // Longest lock a stake position can be given, in slots. Zero disables locks.
+    pub max_lock_period: u64,
+
+    // Governance tokens locked by proposers until their proposal is settled
+    pub proposal_bond: u64,
+
+    // Share of No votes at or above which a failed proposal's bond is slashed
+    pub bond_slash_bps: u16,
+
+    // Whether and how much of the proposal fee is refunded
+    pub fee_refund_policy: FeeRefundPolicy,
+
+    // Most SOL a single proposal can take from the treasury
+    pub max_spend_per_proposal: u64,
+
+    // Most SOL proposals can take from the treasury per epoch
+    pub max_spend_per_epoch: u64,
+
+    // Most a single proposal can take from a token vault, in basis points of its balance
+    pub max_token_spend_per_proposal_bps: u16,
+
+    // Most proposals can take from a token vault per epoch, in basis points of its balance
+    pub max_token_spend_per_epoch_bps: u16,
+
+    // Share of the treasury balance a payout must leave behind
+    pub reserve_floor_bps: u16,
+
+    // Share of Yes votes that bypasses the spending limits
+    pub override_threshold_bps: u16,
+
+    // Optional key allowed to temporarily pause the DAO
+    pub guardian: Option<Pubkey>,
+
+    // Keys that can veto succeeded proposals
+    pub council: Vec<Pubkey>,
+
+    // Optional mint whose holders can veto instead of a list of keys
+    pub council_mint: Option<Pubkey>,
+
+    // Slots after a proposal succeeds during which it can be vetoed
+    pub veto_window: u64,
+}
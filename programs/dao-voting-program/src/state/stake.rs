@@ -1,21 +1,41 @@
 use crate::{constants::*, errors::DaoError};
 use anchor_lang::prelude::*;
 
-// The StakeState account struct represents the staking state of a user in the DAO
+// The StakeState account struct represents one numbered stake position of a user in the DAO.
+// A user can hold several positions, each with its own vault, lock and open vote counter.
 #[account]
 pub struct StakeState {
     // The public key of the account owner (staker)
     pub owner: Pubkey,
 
+    // The wallet whose tokens fund this position: the owner, or a payer through stake_for
+    pub funder: Pubkey,
+
+    // The number of this position among the owner's stake positions
+    pub index: u64,
+
+    // Slot until which the staked tokens can't be withdrawn
+    pub lock_until: u64,
+
     // The total amount of tokens staked by this owner
     pub amount: u64,
 
     // The number of accounts (likely voting accounts) associated with this stake
     pub accounts: u64,
 
+    // The largest amount committed to a vote since the position last had no open votes.
+    // Only the amount above it can be unstaked while votes are open.
+    pub committed: u64,
+
     // The slot at which this stake state was last updated
     pub updated: u64,
 
+    // The reward pool's reward-per-token already accounted for, times the staked amount
+    pub reward_debt: u128,
+
+    // Rewards earned but not yet claimed, in lamports
+    pub pending_rewards: u64,
+
     // Bump seed for the vault's Program Derived Address (PDA)
     pub vault_bump: u8,
 
@@ -30,9 +50,15 @@ impl StakeState {
     // Constant representing the total size of the StakeState account in bytes
     pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
     PUBKEY_LENGTH +    // owner: Pubkey
+    PUBKEY_LENGTH +    // funder: Pubkey
+    U64_LENGTH +       // index: u64
+    U64_LENGTH +       // lock_until: u64
     U64_LENGTH +       // amount: u64
     U64_LENGTH +       // accounts: u64
+    U64_LENGTH +       // committed: u64
     U64_LENGTH +       // updated: u64
+    U128_LENGTH +      // reward_debt: u128
+    U64_LENGTH +       // pending_rewards: u64
     U8_LENGTH +        // vault_bump: u8
     U8_LENGTH +        // auth_bump: u8
     U8_LENGTH; // state_bump: u8
@@ -41,13 +67,25 @@ impl StakeState {
     pub fn init(
         &mut self,
         owner: Pubkey,
+        funder: Pubkey,
+        index: u64,
+        lock_period: u64,
         state_bump: u8,
         vault_bump: u8,
         auth_bump: u8,
     ) -> Result<()> {
         self.owner = owner;
+        self.funder = funder;
+        self.index = index;
+        self.lock_until = Clock::get()?
+            .slot
+            .checked_add(lock_period)
+            .ok_or(DaoError::Overflow)?;
         self.amount = 0;
         self.accounts = 0;
+        self.committed = 0;
+        self.reward_debt = 0;
+        self.pending_rewards = 0;
         self.state_bump = state_bump;
         self.vault_bump = vault_bump;
         self.auth_bump = auth_bump;
@@ -60,23 +98,96 @@ impl StakeState {
         self.update()
     }
 
+    // Pushes the lock out to at least lock_period slots from now
+    pub fn extend_lock(&mut self, lock_period: u64) -> Result<()> {
+        let lock_until = Clock::get()?
+            .slot
+            .checked_add(lock_period)
+            .ok_or(DaoError::Overflow)?;
+        self.lock_until = self.lock_until.max(lock_until);
+        Ok(())
+    }
+
     // Decreases the staked amount, with additional checks
     pub fn unstake(&mut self, amount: u64) -> Result<()> {
-        self.check_accounts()?;
+        self.check_lock()?;
         self.check_slot()?; // Don't allow staking and unstaking in the same slot
         self.amount = self.amount.checked_sub(amount).ok_or(DaoError::Underflow)?;
+        self.check_committed()?; // Tokens committed to open votes stay staked
         self.update()
     }
 
+    // Moves rewards earned since the last settlement into pending_rewards.
+    // Must be called with the pool's current reward_per_token before the amount changes.
+    pub fn settle_rewards(&mut self, reward_per_token: u128) -> Result<()> {
+        let accrued = self.accrued_rewards(reward_per_token)?
+            .checked_sub(self.reward_debt)
+            .ok_or(DaoError::Underflow)?;
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(u64::try_from(accrued).map_err(|_| DaoError::Overflow)?)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    // Marks all rewards up to reward_per_token as accounted for. Must be called after the amount changes.
+    pub fn reset_reward_debt(&mut self, reward_per_token: u128) -> Result<()> {
+        self.reward_debt = self.accrued_rewards(reward_per_token)?;
+        Ok(())
+    }
+
+    // Takes the pending rewards for payout
+    pub fn take_rewards(&mut self) -> Result<u64> {
+        require!(self.pending_rewards > 0, DaoError::NoRewards);
+        let rewards = self.pending_rewards;
+        self.pending_rewards = 0;
+        Ok(rewards)
+    }
+
+    fn accrued_rewards(&self, reward_per_token: u128) -> Result<u128> {
+        Ok((self.amount as u128)
+            .checked_mul(reward_per_token)
+            .ok_or(DaoError::Overflow)?
+            / REWARD_PRECISION)
+    }
+
+    // Ensures every reward has been claimed before the account is closed
+    pub fn check_rewards_claimed(&self) -> Result<()> {
+        require!(self.pending_rewards == 0, DaoError::UnclaimedRewards);
+        Ok(())
+    }
+
     // Increments the number of associated accounts
     pub fn add_account(&mut self) -> Result<()> {
         self.accounts = self.accounts.checked_add(1).ok_or(DaoError::Overflow)?;
         Ok(())
     }
 
-    // Decrements the number of associated accounts
+    // Decrements the number of associated accounts, releasing committed tokens once none are open
     pub fn remove_account(&mut self) -> Result<()> {
         self.accounts = self.accounts.checked_sub(1).ok_or(DaoError::Underflow)?;
+        if self.accounts == 0 {
+            self.committed = 0;
+        }
+        Ok(())
+    }
+
+    // Ensures only the wallet that funded the position adds to it, so rejecting a position
+    // returns exactly what its funder put in
+    pub fn check_funder(&self, funder: Pubkey) -> Result<()> {
+        require_keys_eq!(self.funder, funder, DaoError::InvalidStakeFunder);
+        Ok(())
+    }
+
+    // Ensures the position was funded by someone other than its owner
+    pub fn check_funded_by_other(&self) -> Result<()> {
+        require_keys_neq!(self.funder, self.owner, DaoError::InvalidStakeFunder);
+        Ok(())
+    }
+
+    // Commits staked tokens to an open vote, up to the staked amount
+    pub fn commit(&mut self, amount: u64) -> Result<()> {
+        self.committed = self.committed.max(amount.min(self.amount));
         Ok(())
     }
 
@@ -92,6 +203,18 @@ impl StakeState {
         Ok(())
     }
 
+    // Ensures that the position's lock has expired before unstaking
+    pub fn check_lock(&mut self) -> Result<()> {
+        require!(self.lock_until <= Clock::get()?.slot, DaoError::StakeLocked);
+        Ok(())
+    }
+
+    // Ensures that the tokens committed to open votes remain staked
+    pub fn check_committed(&mut self) -> Result<()> {
+        require!(self.amount >= self.committed, DaoError::StakeCommitted);
+        Ok(())
+    }
+
     // Ensures that the user doesn't have any open accounts before unstaking
     pub fn check_accounts(&mut self) -> Result<()> {
         require!(self.accounts == 0, DaoError::AccountsOpen);
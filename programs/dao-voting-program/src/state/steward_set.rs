@@ -0,0 +1,48 @@
+use crate::constants::*;
+use anchor_lang::prelude::*;
+
+use super::SubTreasury;
+
+// The StewardSet account stores the name and stewards of a sub-treasury for a
+// CreateSubTreasury or SetStewards proposal, so what's applied at execution is
+// what was voted on
+#[account]
+pub struct StewardSet {
+    // The proposal the stewards belong to
+    pub proposal: Pubkey,
+
+    // Name of the sub-treasury to create, or of the one whose stewards are replaced
+    pub name: String,
+
+    // Keys allowed to spend from the sub-treasury's budget
+    pub stewards: Vec<Pubkey>,
+
+    // Bump seed for this steward set's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl StewardSet {
+    /// Total size of the StewardSet account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // proposal: Pubkey
+        4 + MAX_SUB_TREASURY_NAME_LENGTH + // name: String
+        4 + MAX_STEWARDS * PUBKEY_LENGTH + // stewards: Vec<Pubkey>
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new StewardSet account, checking the name and stewards are valid
+    pub fn init(
+        &mut self,
+        proposal: Pubkey,
+        name: String,
+        stewards: Vec<Pubkey>,
+        bump: u8,
+    ) -> Result<()> {
+        SubTreasury::check_name(&name)?;
+        SubTreasury::check_stewards(&stewards)?;
+        self.proposal = proposal;
+        self.name = name;
+        self.stewards = stewards;
+        self.bump = bump;
+        Ok(())
+    }
+}
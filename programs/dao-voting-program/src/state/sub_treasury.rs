@@ -0,0 +1,119 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+
+// The SubTreasury account holds a working group's SOL budget. Governance funds it for
+// a period; its stewards can spend up to the budget without a DAO vote until the
+// period ends, after which unspent funds go back to the main treasury.
+#[account]
+pub struct SubTreasury {
+    // Name of the working group (max 32 characters), used in the PDA derivation
+    pub name: String,
+
+    // Keys allowed to spend from the budget
+    pub stewards: Vec<Pubkey>,
+
+    // SOL approved for the current period
+    pub budget: u64,
+
+    // SOL spent during the current period
+    pub spent: u64,
+
+    // Slot at which the current period ends
+    pub period_end: u64,
+
+    // Bump seed for the sub-treasury's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl SubTreasury {
+    /// Total size of the SubTreasury account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        4 + MAX_SUB_TREASURY_NAME_LENGTH + // name: String
+        4 + MAX_STEWARDS * PUBKEY_LENGTH + // stewards: Vec<Pubkey>
+        U64_LENGTH +       // budget: u64
+        U64_LENGTH +       // spent: u64
+        U64_LENGTH +       // period_end: u64
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new, unfunded SubTreasury account
+    pub fn init(&mut self, name: String, stewards: Vec<Pubkey>, bump: u8) -> Result<()> {
+        Self::check_name(&name)?;
+        Self::check_stewards(&stewards)?;
+        self.name = name;
+        self.stewards = stewards;
+        self.budget = 0;
+        self.spent = 0;
+        self.period_end = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Ensures a name fits in the account and the PDA seeds
+    pub fn check_name(name: &str) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= MAX_SUB_TREASURY_NAME_LENGTH,
+            DaoError::InvalidSubTreasury
+        );
+        Ok(())
+    }
+
+    // Ensures there's at least one steward and no more than fit in the account
+    pub fn check_stewards(stewards: &[Pubkey]) -> Result<()> {
+        require!(
+            !stewards.is_empty() && stewards.len() <= MAX_STEWARDS,
+            DaoError::InvalidSubTreasury
+        );
+        Ok(())
+    }
+
+    // Replaces the stewards, as decided by governance
+    pub fn set_stewards(&mut self, stewards: Vec<Pubkey>) -> Result<()> {
+        Self::check_stewards(&stewards)?;
+        self.stewards = stewards;
+        Ok(())
+    }
+
+    // Starts a new budget period lasting period slots
+    pub fn start_period(&mut self, budget: u64, period: u64) -> Result<()> {
+        self.budget = budget;
+        self.spent = 0;
+        self.period_end = Clock::get()?
+            .slot
+            .checked_add(period)
+            .ok_or(DaoError::Overflow)?;
+        Ok(())
+    }
+
+    // Ends the current period, leaving nothing to spend
+    pub fn end_period(&mut self) {
+        self.budget = 0;
+        self.spent = 0;
+    }
+
+    // Records a steward's spending, checking it's within the period's budget
+    pub fn spend(&mut self, steward: Pubkey, amount: u64) -> Result<()> {
+        self.check_steward(steward)?;
+        require!(
+            Clock::get()?.slot < self.period_end,
+            DaoError::BudgetPeriodEnded
+        );
+        self.spent = self.spent.checked_add(amount).ok_or(DaoError::Overflow)?;
+        require!(self.spent <= self.budget, DaoError::BudgetExceeded);
+        Ok(())
+    }
+
+    // Ensures the key is one of the sub-treasury's stewards
+    pub fn check_steward(&self, steward: Pubkey) -> Result<()> {
+        require!(self.stewards.contains(&steward), DaoError::InvalidSteward);
+        Ok(())
+    }
+
+    // Ensures the current period has ended
+    pub fn check_period_ended(&self) -> Result<()> {
+        require!(
+            Clock::get()?.slot >= self.period_end,
+            DaoError::BudgetPeriodActive
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{constants::*, errors::DaoError};
+use anchor_lang::prelude::*;
+
+use super::DaoSetup;
+
+// The VaultSpend account tracks what proposals take from one of the treasury's token
+// vaults. Token amounts aren't comparable across mints, so the spending limits apply
+// to them as shares of the vault's balance.
+#[account]
+pub struct VaultSpend {
+    // Mint of the treasury vault being tracked, used in the PDA derivation
+    pub mint: Pubkey,
+
+    // The epoch epoch_spent is tracked for
+    pub spend_epoch: u64,
+
+    // Vault balance before the first payout of spend_epoch
+    pub epoch_start_balance: u64,
+
+    // Tokens taken from the vault by proposals during spend_epoch
+    pub epoch_spent: u64,
+
+    // Bump seed for the tracker's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl VaultSpend {
+    /// Total size of the VaultSpend account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // mint: Pubkey
+        U64_LENGTH +       // spend_epoch: u64
+        U64_LENGTH +       // epoch_start_balance: u64
+        U64_LENGTH +       // epoch_spent: u64
+        U8_LENGTH; // bump: u8
+
+    // Initializes a tracker for the vault of a mint
+    pub fn init(&mut self, mint: Pubkey, bump: u8) -> Result<()> {
+        self.mint = mint;
+        self.spend_epoch = 0;
+        self.epoch_start_balance = 0;
+        self.epoch_spent = 0;
+        self.bump = bump;
+        Ok(())
+    }
+
+    /// Records tokens a proposal takes from the vault, enforcing the DAO's token spending
+    /// limits and reserve floor unless the proposal passed with the emergency override's
+    /// supermajority
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The DAO's configuration
+    /// * `amount` - The tokens leaving the vault
+    /// * `vault_balance` - The vault's balance before the payout
+    /// * `yes_share_bps` - The proposal's share of Yes votes, in basis points
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a limit or the reserve floor would be exceeded
+    pub fn record_spend(
+        &mut self,
+        config: &DaoSetup,
+        amount: u64,
+        vault_balance: u64,
+        yes_share_bps: u64,
+    ) -> Result<()> {
+        // Start a new tally each epoch, measured against the balance it started with
+        let epoch = Clock::get()?.epoch;
+        if epoch != self.spend_epoch {
+            self.spend_epoch = epoch;
+            self.epoch_start_balance = vault_balance;
+            self.epoch_spent = 0;
+        }
+        let epoch_spent = self
+            .epoch_spent
+            .checked_add(amount)
+            .ok_or(DaoError::Overflow)?;
+
+        if !config.is_spend_overridden(yes_share_bps) {
+            let per_proposal = config.max_token_spend_per_proposal_bps;
+            require!(
+                per_proposal == 0 || amount <= bps_of(vault_balance, per_proposal)?,
+                DaoError::SpendingLimitExceeded
+            );
+            let per_epoch = config.max_token_spend_per_epoch_bps;
+            require!(
+                per_epoch == 0 || epoch_spent <= bps_of(self.epoch_start_balance, per_epoch)?,
+                DaoError::SpendingLimitExceeded
+            );
+            let reserve = bps_of(vault_balance, config.reserve_floor_bps)?;
+            require!(
+                amount <= vault_balance.saturating_sub(reserve),
+                DaoError::ReserveFloorBreached
+            );
+        }
+
+        self.epoch_spent = epoch_spent;
+        Ok(())
+    }
+}
+
+// The share of amount given in basis points, rounded down
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(DaoError::Overflow)?
+        / BASIS_POINTS as u128;
+    u64::try_from(share).map_err(|_| DaoError::Overflow.into())
+}
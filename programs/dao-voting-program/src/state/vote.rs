@@ -1,20 +1,32 @@
 use crate::{constants::*, errors::DaoError};
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
 
 use super::VoteType;
 
 // The VoteState account struct represents the voting state of a user for a specific proposal
 #[account]
 pub struct VoteState {
+    // The DAO configuration the vote was cast in
+    pub dao: Pubkey,
+
     // The public key of the account owner (voter)
     pub owner: Pubkey,
 
+    // The stake position the vote was cast with
+    pub position: u64,
+
     // The amount of votes cast by this owner
     pub amount: u64,
 
+    // The weight added to the proposal's tally, which may include a reputation bonus
+    pub weight: u64,
+
     /// Enum representing the type of vote
     pub vote_type: VoteType,
 
+    // Optional reasoning published alongside the vote
+    pub rationale: Option<VoteRationale>,
+
     // Bump seed for the vote state's Program Derived Address (PDA)
     pub bump: u8,
 }
@@ -22,23 +34,180 @@ pub struct VoteState {
 impl VoteState {
     /// Total size of the VoteState account in bytes
     pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // dao: Pubkey
         PUBKEY_LENGTH +    // owner: Pubkey
+        U64_LENGTH +       // position: u64
         U64_LENGTH +       // amount: u64
+        U64_LENGTH +       // weight: u64
         ENUM_LENGTH +      // vote_type: VoteType (simple enum, 1 byte)
+        OPTION_LENGTH + VoteRationale::LEN + // rationale: Option<VoteRationale>
         U8_LENGTH; // bump: u8
 
     // Initializes a new VoteState account
     pub fn init(
         &mut self,
+        dao: Pubkey,
         owner: Pubkey,
+        position: u64,
         amount: u64,
+        weight: u64,
         vote_type: VoteType,
         bump: u8,
     ) -> Result<()> {
+        self.dao = dao;
         self.owner = owner;
+        self.position = position;
         self.amount = amount;
+        self.weight = weight;
         self.vote_type = vote_type;
+        self.rationale = None;
+        self.bump = bump;
+        Ok(())
+    }
+
+    // Records the voter's rationale after checking it fits in the account
+    pub fn set_rationale(&mut self, rationale: VoteRationale) -> Result<()> {
+        require!(
+            rationale.uri.len() <= MAX_RATIONALE_LENGTH,
+            DaoError::InvalidRationale
+        );
+        self.rationale = Some(rationale);
+        Ok(())
+    }
+}
+
+// The reasoning behind a vote: short text or a URI, plus a hash of the full content
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VoteRationale {
+    // Short text or URI pointing to the full rationale (max 200 characters)
+    pub uri: String,
+
+    // Hash of the full rationale content
+    pub content_hash: [u8; 32],
+}
+
+impl VoteRationale {
+    /// Maximum serialized size of a VoteRationale in bytes
+    pub const LEN: usize = 4 + MAX_RATIONALE_LENGTH + // uri: String (4 bytes for length + max 200 characters)
+        32; // content_hash: [u8; 32]
+}
+
+// The voter's rationale as returned by the get_vote_rationale view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VoteRationaleView {
+    pub voter: Pubkey,
+    pub proposal_id: u64,
+    pub amount: u64,
+    pub weight: u64,
+    pub vote_type: VoteType,
+    pub rationale: Option<VoteRationale>,
+}
+
+// The VoteCommitment account struct holds a sealed vote until it is revealed
+#[account]
+pub struct VoteCommitment {
+    // The DAO configuration the commitment was made in
+    pub dao: Pubkey,
+
+    // The public key of the account owner (voter)
+    pub owner: Pubkey,
+
+    // The stake position locked by the commitment
+    pub position: u64,
+
+    // Hash of the voter, vote type, amount and salt
+    pub commitment: [u8; 32],
+
+    // Whether the commitment is penalized if it is never revealed, kept so the penalty
+    // still applies once the proposal is closed
+    pub slash_unrevealed: bool,
+
+    // Bump seed for the commitment's Program Derived Address (PDA)
+    pub bump: u8,
+}
+
+impl VoteCommitment {
+    /// Total size of the VoteCommitment account in bytes
+    pub const LEN: usize = 8 +                // Discriminator (added by Anchor)
+        PUBKEY_LENGTH +    // dao: Pubkey
+        PUBKEY_LENGTH +    // owner: Pubkey
+        U64_LENGTH +       // position: u64
+        32 +               // commitment: [u8; 32]
+        BOOL_LENGTH +      // slash_unrevealed: bool
+        U8_LENGTH; // bump: u8
+
+    // Initializes a new VoteCommitment account
+    pub fn init(
+        &mut self,
+        dao: Pubkey,
+        owner: Pubkey,
+        position: u64,
+        commitment: [u8; 32],
+        slash_unrevealed: bool,
+        bump: u8,
+    ) -> Result<()> {
+        self.dao = dao;
+        self.owner = owner;
+        self.position = position;
+        self.commitment = commitment;
+        self.slash_unrevealed = slash_unrevealed;
         self.bump = bump;
         Ok(())
     }
+
+    // Computes the commitment for a vote. The owner is part of the preimage so a
+    // commitment can't be copied by another voter and revealed once the original is.
+    pub fn hash(owner: &Pubkey, vote_type: VoteType, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[
+            owner.as_ref(),
+            &[vote_type as u8],
+            &amount.to_le_bytes(),
+            salt,
+        ])
+        .to_bytes()
+    }
+
+    // Ensures a revealed vote matches the stored commitment
+    pub fn verify(&self, vote_type: VoteType, amount: u64, salt: &[u8; 32]) -> Result<()> {
+        require!(
+            Self::hash(&self.owner, vote_type, amount, salt) == self.commitment,
+            DaoError::InvalidCommitment
+        );
+        Ok(())
+    }
+}
+
+// A single vote within a batch vote instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchVoteItem {
+    // The amount of votes cast
+    pub amount: u64,
+
+    // The choice of the voter
+    pub vote_type: VoteType,
+
+    // Optional reasoning published alongside the vote
+    pub rationale: Option<VoteRationale>,
+}
+
+// The message a member signs off-chain to authorize a relayer to vote on their behalf
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VoteMessage {
+    // The DAO configuration account the vote belongs to
+    pub dao: Pubkey,
+
+    // The ID of the proposal being voted on
+    pub proposal_id: u64,
+
+    // The stake position to vote with
+    pub position: u64,
+
+    // The choice of the voter
+    pub vote_type: VoteType,
+
+    // The amount of votes cast
+    pub amount: u64,
+
+    // Must match the member's current nonce, preventing replays
+    pub nonce: u64,
 }
@@ -0,0 +1,67 @@
+use crate::errors::DaoError;
+use anchor_lang::prelude::*;
+
+// The VoterWeightRecord account is written by an external voter-weight plugin program
+// and read by this program in place of the raw stake. Plugins must store it with the
+// same layout and Anchor discriminator ("account:VoterWeightRecord").
+#[account]
+pub struct VoterWeightRecord {
+    // The DAO configuration account the weight applies to
+    pub dao: Pubkey,
+
+    // The member the weight belongs to
+    pub voter: Pubkey,
+
+    // The voting weight computed by the plugin
+    pub weight: u64,
+
+    // Last slot at which the weight is valid, if it expires
+    pub expiry_slot: Option<u64>,
+
+    // The action the weight was computed for, if restricted to one
+    pub action: Option<VoterWeightAction>,
+
+    // The account the action targets (e.g. a proposal), if restricted to one
+    pub action_target: Option<Pubkey>,
+}
+
+impl VoterWeightRecord {
+    // Deserializes a record, ensuring it is owned by the DAO's trusted plugin program
+    pub fn load(info: &AccountInfo, plugin: &Pubkey) -> Result<Self> {
+        require_keys_eq!(*info.owner, *plugin, DaoError::InvalidVoterWeightRecord);
+        let data = info.try_borrow_data()?;
+        Self::try_deserialize(&mut &data[..])
+    }
+
+    // Returns the weight after checking the record applies to this DAO, voter and action
+    pub fn check_weight(
+        &self,
+        dao: Pubkey,
+        voter: Pubkey,
+        action: VoterWeightAction,
+        target: Option<Pubkey>,
+    ) -> Result<u64> {
+        require_keys_eq!(self.dao, dao, DaoError::InvalidVoterWeightRecord);
+        require_keys_eq!(self.voter, voter, DaoError::InvalidVoterWeightRecord);
+        if let Some(expiry_slot) = self.expiry_slot {
+            require!(
+                Clock::get()?.slot <= expiry_slot,
+                DaoError::VoterWeightExpired
+            );
+        }
+        if let Some(scope) = self.action {
+            require!(scope == action, DaoError::InvalidVoterWeightRecord);
+        }
+        if let (Some(scope), Some(target)) = (self.action_target, target) {
+            require_keys_eq!(scope, target, DaoError::InvalidVoterWeightRecord);
+        }
+        Ok(self.weight)
+    }
+}
+
+/// Enum representing the governance action a voter weight was computed for
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VoterWeightAction {
+    CastVote,
+    CreateProposal,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer},
+};
+
+// Creates a program-owned PDA the way Anchor's `init` does. If someone already sent lamports
+// to the address, create_account would fail, so the balance is topped up to rent exemption
+// and the account allocated and assigned instead. Either way this fails if the account
+// already holds data.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    space: usize,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = target.lamports();
+    if lamports == 0 {
+        let ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: target.clone(),
+            },
+            signer_seeds,
+        );
+        return create_account(ctx, rent, space as u64, &crate::ID);
+    }
+
+    let top_up = rent.saturating_sub(lamports);
+    if top_up > 0 {
+        let ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: target.clone(),
+            },
+        );
+        transfer(ctx, top_up)?;
+    }
+    let ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Allocate {
+            account_to_allocate: target.clone(),
+        },
+        signer_seeds,
+    );
+    allocate(ctx, space as u64)?;
+    let ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Assign {
+            account_to_assign: target.clone(),
+        },
+        signer_seeds,
+    );
+    assign(ctx, &crate::ID)
+}
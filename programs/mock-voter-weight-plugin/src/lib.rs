@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+declare_id!("ALrNGxAWjrQWoLrNEr3QT7REdir3nKCKTnnWkfuauzU4");
+
+// A stand-in for real voter-weight plugins (NFT, reputation, ...). It lets anyone
+// write an arbitrary weight for themselves, so it must only ever be used in tests.
+#[program]
+pub mod mock_voter_weight_plugin {
+    use super::*;
+
+    // Create or update the caller's voter weight record for a DAO
+    pub fn set_voter_weight(
+        ctx: Context<SetVoterWeight>,
+        dao: Pubkey,
+        weight: u64,
+        expiry_slot: Option<u64>,
+        action: Option<VoterWeightAction>,
+        action_target: Option<Pubkey>,
+    ) -> Result<()> {
+        let record = &mut ctx.accounts.voter_weight_record;
+        record.dao = dao;
+        record.voter = ctx.accounts.voter.key();
+        record.weight = weight;
+        record.expiry_slot = expiry_slot;
+        record.action = action;
+        record.action_target = action_target;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(dao: Pubkey)]
+pub struct SetVoterWeight<'info> {
+    #[account(mut)]
+    voter: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = voter,
+        seeds = [b"voter-weight", dao.as_ref(), voter.key().as_ref()],
+        bump,
+        space = VoterWeightRecord::LEN
+    )]
+    voter_weight_record: Account<'info, VoterWeightRecord>,
+    system_program: Program<'info, System>,
+}
+
+// Must match the layout of VoterWeightRecord in the DAO voting program
+#[account]
+pub struct VoterWeightRecord {
+    pub dao: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub expiry_slot: Option<u64>,
+    pub action: Option<VoterWeightAction>,
+    pub action_target: Option<Pubkey>,
+}
+
+impl VoterWeightRecord {
+    pub const LEN: usize = 8 + // Discriminator (added by Anchor)
+        32 +               // dao: Pubkey
+        32 +               // voter: Pubkey
+        8 +                // weight: u64
+        1 + 8 +            // expiry_slot: Option<u64>
+        1 + 1 +            // action: Option<VoterWeightAction>
+        1 + 32; // action_target: Option<Pubkey>
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VoterWeightAction {
+    CastVote,
+    CreateProposal,
+}
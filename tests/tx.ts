@@ -1,7 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { DaoVotingProgram } from "../target/types/dao_voting_program";
+import { MockVoterWeightPlugin } from "../target/types/mock_voter_weight_plugin";
 import {
+    AccountMeta,
+    Ed25519Program,
     Keypair,
     LAMPORTS_PER_SOL,
     PublicKey,
@@ -18,10 +21,20 @@ import {
     getMinimumBalanceForRentExemptMint,
     getAccount,
     createAssociatedTokenAccount,
+    createAssociatedTokenAccountIdempotent,
     createAssociatedTokenAccountInstruction,
+    createMint,
+    mintTo,
+    transfer,
     ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { randomBytes } from "crypto";
+import {
+    PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+    createCreateMasterEditionV3Instruction,
+    createCreateMetadataAccountV3Instruction,
+    createVerifyCollectionInstruction,
+} from "@metaplex-foundation/mpl-token-metadata";
+import { createHash, randomBytes } from "crypto";
 import assert from "assert";
 
 describe("dao-voting-program", () => {
@@ -50,6 +63,558 @@ describe("dao-voting-program", () => {
         return signature;
     };
 
+    // Default settings for DAOs created by the tests, overridden where a test needs a feature
+    const daoSettings = (overrides: Record<string, any> = {}) => ({
+        issuePrice: new BN(1 * LAMPORTS_PER_SOL),
+        issueAmount: new BN(100),
+        proposalFee: new BN(0.1 * LAMPORTS_PER_SOL),
+        maxSupply: new BN(1e6),
+        minQuorum: new BN(100),
+        maxExpiry: new BN(100000),
+        voterWeightPlugin: null,
+        nftCollection: null,
+        nftVoteWeight: new BN(0),
+        nftWeightAuthority: null,
+        reputationCoefficientBps: 0,
+        liquidStaking: false,
+        maxLockPeriod: new BN(100000),
+        proposalBond: new BN(0),
+        bondSlashBps: 0,
+        feeRefundPolicy: { keep: {} },
+        maxSpendPerProposal: new BN(0),
+        maxSpendPerEpoch: new BN(0),
+        maxTokenSpendPerProposalBps: 0,
+        maxTokenSpendPerEpochBps: 0,
+        reserveFloorBps: 0,
+        overrideThresholdBps: 0,
+        guardian: null,
+        council: [],
+        councilMint: null,
+        vetoWindow: new BN(0),
+        ...overrides,
+    });
+
+    const pda = (...seeds: Buffer[]) =>
+        PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+    // Creates a fresh DAO with the given settings and returns its accounts
+    const createDao = async (overrides: Record<string, any> = {}) => {
+        const daoSeed = new BN(randomBytes(8));
+        const settings = daoSettings(overrides);
+        const config = pda(Buffer.from("config"), daoSeed.toArrayLike(Buffer, "le", 8));
+        const dao = {
+            settings,
+            config,
+            treasury: pda(Buffer.from("treasury"), config.toBuffer()),
+            auth: pda(Buffer.from("auth"), config.toBuffer()),
+            rewardPool: pda(Buffer.from("rewards"), config.toBuffer()),
+            mint: pda(Buffer.from("mint"), config.toBuffer()),
+            receiptMint: pda(Buffer.from("receipt"), config.toBuffer()),
+        };
+        await program.methods
+            .initialize(daoSeed, settings)
+            .accounts({
+                rewardPool: dao.rewardPool,
+                initializer: provider.wallet.publicKey,
+                mint: dao.mint,
+                receiptMint: settings.liquidStaking ? dao.receiptMint : null,
+                config,
+                treasury: dao.treasury,
+                auth: dao.auth,
+                tokenProgram,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc()
+            .then(confirm);
+        return dao;
+    };
+
+    type Dao = Awaited<ReturnType<typeof createDao>>;
+
+    // Buys one issue of governance tokens into the user's token account
+    const buyTokens = async (dao: Dao, user: Keypair) => {
+        const ata = getAssociatedTokenAddressSync(dao.mint, user.publicKey);
+        await program.methods
+            .issueTokens()
+            .accounts({
+                initializer: user.publicKey,
+                initializerAta: ata,
+                auth: dao.auth,
+                treasury: dao.treasury,
+                mint: dao.mint,
+                config: dao.config,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user])
+            .rpc()
+            .then(confirm);
+        return ata;
+    };
+
+    // Stakes into one of the user's positions and returns the position's accounts
+    const stakeTokens = async (dao: Dao, user: Keypair, index: BN, amount: BN, lockPeriod = new BN(0)) => {
+        const ownerAta = await createAssociatedTokenAccountIdempotent(connection, user, dao.mint, user.publicKey);
+        const liquid = dao.settings.liquidStaking;
+        const receiptAccount = liquid
+            ? await createAssociatedTokenAccountIdempotent(connection, user, dao.receiptMint, user.publicKey)
+            : null;
+        const position = {
+            owner: user,
+            ownerAta,
+            receiptAccount,
+            stakeAta: pda(Buffer.from("vault"), dao.config.toBuffer(), user.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)),
+            stakeState: pda(Buffer.from("stake"), dao.config.toBuffer(), user.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)),
+            stakeAuth: pda(Buffer.from("auth"), dao.config.toBuffer(), user.publicKey.toBuffer()),
+            memberState: pda(Buffer.from("member"), dao.config.toBuffer(), user.publicKey.toBuffer()),
+        };
+        await program.methods
+            .stakeTokens(index, amount, lockPeriod)
+            .accounts({
+                rewardPool: dao.rewardPool,
+                owner: user.publicKey,
+                ownerAta,
+                stakeAta: position.stakeAta,
+                mint: dao.mint,
+                auth: position.stakeAuth,
+                stakeState: position.stakeState,
+                memberState: position.memberState,
+                receiptMint: liquid ? dao.receiptMint : null,
+                receiptAccount,
+                daoAuth: liquid ? dao.auth : null,
+                config: dao.config,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            })
+            .signers([user])
+            .rpc()
+            .then(confirm);
+        return position;
+    };
+
+    type Position = Awaited<ReturnType<typeof stakeTokens>>;
+
+    // Creates the DAO's next proposal from a position and returns its address
+    const propose = async (
+        dao: Dao,
+        position: Position,
+        proposalType: Record<string, any> = { vote: {} },
+        expiry = new BN(100),
+        ballot: Record<string, any> = { public: {} },
+        quorum: BN = dao.settings.minQuorum,
+        accounts: Record<string, any> = {},
+    ) => {
+        const { proposalCount } = await program.account.daoSetup.fetch(dao.config);
+        const id = proposalCount.addn(1);
+        const proposal = pda(Buffer.from("proposal"), dao.config.toBuffer(), id.toArrayLike(Buffer, "le", 8));
+        await program.methods
+            .createProposal(id, `Proposal ${id}`, "https://example.com/proposal", proposalType, quorum, expiry, ballot)
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                memberState: position.memberState,
+                treasury: dao.treasury,
+                config: dao.config,
+                receiptAccount: position.receiptAccount,
+                auth: dao.auth,
+                mint: dao.mint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                ...accounts,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+        return proposal;
+    };
+
+    // Casts a public vote from a position
+    const castVote = (dao: Dao, position: Position, proposal: PublicKey, amount: BN, voteType: Record<string, any> = { yes: {} }) => {
+        const liquid = dao.settings.liquidStaking;
+        return program.methods
+            .vote(amount, voteType)
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                vote: pda(Buffer.from("vote"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                memberState: position.memberState,
+                config: dao.config,
+                receiptAccount: position.receiptAccount,
+                receiptMint: liquid ? dao.receiptMint : null,
+                daoAuth: liquid ? dao.auth : null,
+                tokenProgram: liquid ? TOKEN_PROGRAM_ID : null,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+    };
+
+    // Closes a position's vote once voting on the proposal is over, even if it was executed
+    const cleanupVote = (dao: Dao, position: Position, proposal: PublicKey) =>
+        program.methods
+            .cleanupVote()
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                vote: pda(Buffer.from("vote"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                memberState: position.memberState,
+                treasury: dao.treasury,
+                config: dao.config,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Seals a vote the way VoteCommitment::hash does
+    const sealVote = (owner: PublicKey, voteType: number, amount: BN, salt: Buffer) =>
+        Array.from(
+            createHash("sha256")
+                .update(Buffer.concat([owner.toBuffer(), Buffer.from([voteType]), amount.toArrayLike(Buffer, "le", 8), salt]))
+                .digest(),
+        );
+
+    // Commits a sealed vote from a position
+    const commitVote = (dao: Dao, position: Position, proposal: PublicKey, commitment: number[]) =>
+        program.methods
+            .commitVote(commitment)
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                commitment: pda(Buffer.from("commit"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                memberState: position.memberState,
+                config: dao.config,
+                receiptAccount: position.receiptAccount,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Reveals a sealed vote from a position
+    const revealVote = (dao: Dao, position: Position, proposal: PublicKey, amount: BN, voteType: Record<string, any>, salt: Buffer) =>
+        program.methods
+            .revealVote(amount, voteType, Array.from(salt))
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                commitment: pda(Buffer.from("commit"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                vote: pda(Buffer.from("vote"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                memberState: position.memberState,
+                config: dao.config,
+                receiptAccount: position.receiptAccount,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Discards a sealed vote that was never revealed
+    const cleanupCommit = (dao: Dao, position: Position, proposal: PublicKey) =>
+        program.methods
+            .cleanupCommit()
+            .accounts({
+                owner: position.owner.publicKey,
+                stakeState: position.stakeState,
+                proposal,
+                commitment: pda(Buffer.from("commit"), proposal.toBuffer(), position.owner.publicKey.toBuffer()),
+                treasury: dao.treasury,
+                memberState: position.memberState,
+                config: dao.config,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Thaws a position's receipt account once its votes are closed
+    const thawReceipts = (dao: Dao, position: Position) =>
+        program.methods
+            .thawReceipts()
+            .accounts({
+                owner: position.owner.publicKey,
+                receiptAccount: position.receiptAccount,
+                receiptMint: dao.receiptMint,
+                memberState: position.memberState,
+                daoAuth: dao.auth,
+                config: dao.config,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Unstakes tokens from a position back to the owner's token account
+    const unstake = (dao: Dao, position: Position, amount: BN) => {
+        const liquid = dao.settings.liquidStaking;
+        return program.methods
+            .unstakeTokens(amount)
+            .accounts({
+                rewardPool: dao.rewardPool,
+                owner: position.owner.publicKey,
+                ownerAta: position.ownerAta,
+                stakeAta: position.stakeAta,
+                auth: position.stakeAuth,
+                mint: dao.mint,
+                stakeState: position.stakeState,
+                config: dao.config,
+                receiptMint: liquid ? dao.receiptMint : null,
+                receiptAccount: position.receiptAccount,
+                daoAuth: liquid ? dao.auth : null,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+    };
+
+    // Leaves the DAO from a member's last position
+    const exitDao = (dao: Dao, position: Position) =>
+        program.methods
+            .exitDao()
+            .accounts({
+                owner: position.owner.publicKey,
+                ownerAta: position.ownerAta,
+                stakeAta: position.stakeAta,
+                stakeAuth: position.stakeAuth,
+                mint: dao.mint,
+                stakeState: position.stakeState,
+                memberState: position.memberState,
+                rewardPool: dao.rewardPool,
+                config: dao.config,
+                receiptMint: dao.settings.liquidStaking ? dao.receiptMint : null,
+                receiptAccount: position.receiptAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([position.owner])
+            .rpc()
+            .then(confirm);
+
+    // Executes a succeeded proposal, with whatever accounts its type needs
+    const execute = (
+        dao: Dao,
+        proposal: PublicKey,
+        proposer: Position,
+        accounts: Record<string, any> = {},
+        signer: Keypair = proposer.owner,
+        remainingAccounts: AccountMeta[] = [],
+    ) =>
+        program.methods
+            .executeProposal()
+            .accounts({
+                initializer: signer.publicKey,
+                payee: Keypair.generate().publicKey,
+                proposal,
+                proposerState: proposer.memberState,
+                treasury: dao.treasury,
+                config: dao.config,
+                auth: dao.auth,
+                mint: dao.mint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                ...accounts,
+            })
+            .remainingAccounts(remainingAccounts)
+            .signers([signer])
+            .rpc()
+            .then(confirm);
+
+    // Gives the treasury a vault for a new mint holding the given amount
+    const createTokenVault = async (dao: Dao, amount: number) => {
+        const tokenMint = await createMint(connection, user1, user1.publicKey, null, 0);
+        const vault = {
+            mint: tokenMint,
+            treasuryVault: getAssociatedTokenAddressSync(tokenMint, dao.treasury, true),
+            vaultSpend: pda(Buffer.from("vault_spend"), dao.config.toBuffer(), tokenMint.toBuffer()),
+        };
+        await program.methods
+            .createTreasuryVault()
+            .accounts({
+                payer: user1.publicKey,
+                treasury: dao.treasury,
+                mint: tokenMint,
+                treasuryVault: vault.treasuryVault,
+                vaultSpend: vault.vaultSpend,
+                config: dao.config,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user1])
+            .rpc()
+            .then(confirm);
+        await mintTo(connection, user1, tokenMint, vault.treasuryVault, user1, amount);
+        return vault;
+    };
+
+    // Burns a holder's unstaked tokens for a share of the treasury
+    const ragequit = (dao: Dao, owner: Keypair, amount: BN) =>
+        program.methods
+            .ragequit(amount)
+            .accounts({
+                owner: owner.publicKey,
+                ownerAta: getAssociatedTokenAddressSync(dao.mint, owner.publicKey),
+                mint: dao.mint,
+                memberState: pda(Buffer.from("member"), dao.config.toBuffer(), owner.publicKey.toBuffer()),
+                treasury: dao.treasury,
+                config: dao.config,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc()
+            .then(confirm);
+
+    const metadataOf = (mint: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+            TOKEN_METADATA_PROGRAM_ID
+        )[0];
+
+    const editionOf = (mint: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+            TOKEN_METADATA_PROGRAM_ID
+        )[0];
+
+    // Mints a one-of-one NFT to the owner, verified as part of the collection when one is given
+    const mintNft = async (owner: PublicKey, collection?: PublicKey) => {
+        const payer = (provider.wallet as anchor.Wallet).payer;
+        const mint = await createMint(connection, payer, payer.publicKey, payer.publicKey, 0);
+        const tokenAccount = await createAssociatedTokenAccount(connection, payer, mint, owner);
+        await mintTo(connection, payer, mint, tokenAccount, payer, 1);
+        const metadata = metadataOf(mint);
+        const tx = new Transaction().add(
+            createCreateMetadataAccountV3Instruction(
+                { metadata, mint, mintAuthority: payer.publicKey, payer: payer.publicKey, updateAuthority: payer.publicKey },
+                {
+                    createMetadataAccountArgsV3: {
+                        data: {
+                            name: "Member",
+                            symbol: "DAO",
+                            uri: "https://example.com/nft.json",
+                            sellerFeeBasisPoints: 0,
+                            creators: null,
+                            collection: collection ? { key: collection, verified: false } : null,
+                            uses: null,
+                        },
+                        isMutable: true,
+                        collectionDetails: null,
+                    },
+                }
+            ),
+            createCreateMasterEditionV3Instruction(
+                { edition: editionOf(mint), mint, updateAuthority: payer.publicKey, mintAuthority: payer.publicKey, payer: payer.publicKey, metadata },
+                { createMasterEditionArgs: { maxSupply: 0 } }
+            )
+        );
+        if (collection) {
+            tx.add(
+                createVerifyCollectionInstruction({
+                    metadata,
+                    collectionAuthority: payer.publicKey,
+                    payer: payer.publicKey,
+                    collectionMint: collection,
+                    collection: metadataOf(collection),
+                    collectionMasterEditionAccount: editionOf(collection),
+                })
+            );
+        }
+        await provider.sendAndConfirm(tx);
+        return { mint, metadata, tokenAccount };
+    };
+
+    type Nft = Awaited<ReturnType<typeof mintNft>>;
+
+    // Proposes in an NFT DAO, holding the given NFT instead of a stake position
+    const proposeWithNft = async (dao: Dao, owner: Keypair, nft: Nft) => {
+        const { proposalCount } = await program.account.daoSetup.fetch(dao.config);
+        const id = proposalCount.addn(1);
+        const proposal = pda(Buffer.from("proposal"), dao.config.toBuffer(), id.toArrayLike(Buffer, "le", 8));
+        await program.methods
+            .createProposal(id, `Proposal ${id}`, "https://example.com/proposal", { vote: {} }, dao.settings.minQuorum, new BN(100), { public: {} })
+            .accounts({
+                owner: owner.publicKey,
+                stakeState: null,
+                proposal,
+                memberState: pda(Buffer.from("member"), dao.config.toBuffer(), owner.publicKey.toBuffer()),
+                treasury: dao.treasury,
+                config: dao.config,
+                nftTokenAccount: nft.tokenAccount,
+                nftMetadata: nft.metadata,
+                auth: dao.auth,
+                mint: dao.mint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([owner])
+            .rpc()
+            .then(confirm);
+        return proposal;
+    };
+
+    const nftVoteRecord = (proposal: PublicKey, nft: Nft) =>
+        pda(Buffer.from("nft_vote"), proposal.toBuffer(), nft.mint.toBuffer());
+
+    const nftWeight = (dao: Dao, nft: Nft) =>
+        pda(Buffer.from("nft_weight"), dao.config.toBuffer(), nft.mint.toBuffer());
+
+    // Votes with NFTs, passing each NFT's weight account when the DAO weighs NFTs by their traits
+    const nftVote = (dao: Dao, voter: Keypair, proposal: PublicKey, nfts: Nft[], weights: PublicKey[] = []) =>
+        program.methods
+            .nftVote({ yes: {} })
+            .accounts({
+                owner: voter.publicKey,
+                proposal,
+                memberState: pda(Buffer.from("member"), dao.config.toBuffer(), voter.publicKey.toBuffer()),
+                config: dao.config,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(
+                nfts.flatMap((nft, i) => [
+                    { pubkey: nft.tokenAccount, isSigner: false, isWritable: false },
+                    { pubkey: nft.metadata, isSigner: false, isWritable: false },
+                    ...(dao.settings.nftWeightAuthority ? [{ pubkey: weights[i] ?? nftWeight(dao, nft), isSigner: false, isWritable: false }] : []),
+                    { pubkey: nftVoteRecord(proposal, nft), isSigner: false, isWritable: true },
+                ])
+            )
+            .signers([voter])
+            .rpc()
+            .then(confirm);
+
+    // Records the weight an NFT's traits earn
+    const setNftWeight = (dao: Dao, authority: Keypair, nft: Nft, weight: BN) =>
+        program.methods
+            .setNftWeight(weight)
+            .accounts({
+                authority: authority.publicKey,
+                nftMint: nft.mint,
+                nftMetadata: nft.metadata,
+                nftWeight: nftWeight(dao, nft),
+                config: dao.config,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([authority])
+            .rpc()
+            .then(confirm);
+
+    // Sends SOL to a DAO's treasury
+    const donate = async (dao: Dao, from: Keypair, lamports: number) => {
+        const tx = new Transaction().add(
+            SystemProgram.transfer({ fromPubkey: from.publicKey, toPubkey: dao.treasury, lamports })
+        );
+        await provider.sendAndConfirm(tx, [from]);
+    };
+
     // let mintKeypair: Keypair;
     // let mint: PublicKey;
 
@@ -63,6 +628,7 @@ describe("dao-voting-program", () => {
     let configPda: PublicKey;
     let treasuryPda: PublicKey;
     let authPda: PublicKey;
+    let rewardPoolPda: PublicKey;
 
     let user1StakeAta: PublicKey;
     let user1StakeState: PublicKey;
@@ -98,6 +664,10 @@ describe("dao-voting-program", () => {
             [Buffer.from("auth"), configPda.toBuffer()],
             program.programId
         );
+        [rewardPoolPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("rewards"), configPda.toBuffer()],
+            program.programId
+        );
 
         [mintPda] = PublicKey.findProgramAddressSync(
             [Buffer.from("mint"), configPda.toBuffer()],
@@ -175,11 +745,11 @@ describe("dao-voting-program", () => {
 
         //Derive other PDAs
         [user1StakeAta] = PublicKey.findProgramAddressSync(
-            [Buffer.from("vault"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+            [Buffer.from("vault"), configPda.toBuffer(), user1.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
             program.programId
         );
         [user1StakeState] = PublicKey.findProgramAddressSync(
-            [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer()],
+            [Buffer.from("stake"), configPda.toBuffer(), user1.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
             program.programId
         );
         [user1MemberState] = PublicKey.findProgramAddressSync(
@@ -217,18 +787,12 @@ describe("dao-voting-program", () => {
             program.programId
         );
         await program.methods
-            .initialize(
-                seed,
-                new BN(1 * LAMPORTS_PER_SOL), // issue_price
-                new BN(100), // issue_amount
-                new BN(0.1 * LAMPORTS_PER_SOL), // proposal_fee
-                new BN(1e6), // max_supply
-                new BN(100), // min_quorum
-                new BN(100000) // max_expiry
-            )
+            .initialize(seed, daoSettings())
             .accounts({
+                rewardPool: rewardPoolPda,
                 initializer: provider.wallet.publicKey,
                 mint: mintPda,
+                receiptMint: null,
                 config: configPda,
                 treasury: treasuryPda,
                 auth: authPda,
@@ -249,37 +813,12 @@ describe("dao-voting-program", () => {
         // assert.equal(config.proposalFee.toString(), (0.1 * LAMPORTS_PER_SOL).toString(), "Proposal fee should match");
     });
 
-    it("Initialize stake", async () => {
-        await program.methods
-            .initStake()
-            .accounts({
-                owner: user1.publicKey,
-                ownerAta: user1Ata,
-                stakeAta: user1StakeAta,
-                stakeAuth: authPda,
-                mint: mintPda,
-                stakeState: user1StakeState,
-                config: configPda,
-                tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-                systemProgram: SystemProgram.programId,
-            })
-            .signers([user1])
-            .rpc()
-        // .then(confirm)
-        // .then(log);
-
-        const stakeState = await program.account.stakeState.fetch(user1StakeState);
-        //assert.equal(stakeState.owner.toBase58(), user1.publicKey.toBase58(), "Stake state owner should be user1");
-        assert.ok(stakeState, "Stake should be initialized");
-    });
-
-    
     it("Stake tokens", async () => {
         const amount = new BN(50);
         await program.methods
-            .stakeTokens(amount)
+            .stakeTokens(new BN(0), amount, new BN(0)) // position 0, unlocked
             .accounts({
+                rewardPool: rewardPoolPda,
                 owner: user1.publicKey,
                 ownerAta: user1Ata,
                 stakeAta: user1StakeAta,
@@ -433,7 +972,8 @@ describe("dao-voting-program", () => {
                 "https://example.com/proposal",
                 { vote: {} },
                 new BN(10), // threshold
-                new BN(100) // expiry
+                new BN(100), // expiry
+                { public: {} } // ballot
             )
             .accounts({
                 owner: user1.publicKey,
@@ -442,6 +982,9 @@ describe("dao-voting-program", () => {
                 memberState: user1MemberState,
                 treasury: treasuryPda,
                 config: configPda,
+                auth: authPda,
+                mint: mintPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
             })
             .signers([user1])
@@ -491,6 +1034,107 @@ describe("dao-voting-program", () => {
         assert.equal(results.abstainVotes.toString(), "0", "Abstain votes should be 0");
     });
 
+    it("Vote by signature", async () => {
+        // user2 gets tokens and stakes, but never signs a vote transaction
+        user2Ata = getAssociatedTokenAddressSync(mintPda, user2.publicKey);
+        const [user2StakeAta] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), configPda.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [user2StakeState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [user2MemberState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("member"), configPda.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+        const [user2VotePda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vote"), proposalPda.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+
+        await program.methods
+            .issueTokens()
+            .accounts({
+                initializer: user2.publicKey,
+                initializerAta: user2Ata,
+                auth: authPda,
+                treasury: treasuryPda,
+                mint: mintPda,
+                config: configPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        await program.methods
+            .stakeTokens(new BN(0), new BN(20), new BN(0))
+            .accounts({
+                rewardPool: rewardPoolPda,
+                owner: user2.publicKey,
+                ownerAta: user2Ata,
+                stakeAta: user2StakeAta,
+                mint: mintPda,
+                auth: authPda,
+                stakeState: user2StakeState,
+                memberState: user2MemberState,
+                config: configPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        // Borsh layout of VoteMessage: dao, proposal_id, position, vote_type, amount, nonce
+        const amount = new BN(20);
+        // Each membership starts its nonces at its own offset
+        const { nonce } = await program.account.memberState.fetch(user2MemberState);
+        const message = Buffer.concat([
+            configPda.toBuffer(),
+            proposalId.toArrayLike(Buffer, "le", 8),
+            new BN(0).toArrayLike(Buffer, "le", 8), // position
+            Buffer.from([1]), // VoteType::No
+            amount.toArrayLike(Buffer, "le", 8),
+            nonce.toArrayLike(Buffer, "le", 8),
+        ]);
+        const signatureIx = Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: user2.secretKey,
+            message,
+        });
+
+        // The provider wallet relays the vote and pays for the vote account
+        await program.methods
+            .voteBySignature(amount, { no: {} }, nonce)
+            .accounts({
+                relayer: provider.wallet.publicKey,
+                voter: user2.publicKey,
+                stakeState: user2StakeState,
+                proposal: proposalPda,
+                vote: user2VotePda,
+                memberState: user2MemberState,
+                config: configPda,
+                instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+                systemProgram: SystemProgram.programId,
+            })
+            .preInstructions([signatureIx])
+            .rpc()
+            .then(confirm)
+            .then(log);
+
+        const vote = await program.account.voteState.fetch(user2VotePda);
+        assert.equal(vote.amount.toString(), "20", "Vote amount should be 20");
+        assert.deepEqual(vote.voteType, { no: {} }, "Vote type should be 'no'");
+
+        const memberState = await program.account.memberState.fetch(user2MemberState);
+        assert.equal(memberState.nonce.toString(), nonce.addn(1).toString(), "Nonce should be consumed");
+    });
+
     it("Remove vote", async () => {
         // Add a delay to avoid InvalidSlot error
         await new Promise(resolve => setTimeout(resolve, 1000));
@@ -546,6 +1190,9 @@ describe("dao-voting-program", () => {
                 treasury: treasuryPda,
                 config: configPda,
                 proposerState: user1MemberState,
+                auth: authPda,
+                mint: mintPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
             })
             .signers([user1])
@@ -567,6 +1214,7 @@ describe("dao-voting-program", () => {
         await program.methods
             .unstakeTokens(amount)
             .accounts({
+                rewardPool: rewardPoolPda,
                 owner: user1.publicKey,
                 ownerAta: user1Ata,
                 stakeAta: user1StakeAta,
@@ -574,10 +1222,7 @@ describe("dao-voting-program", () => {
                 mint: mintPda,
                 stakeState: user1StakeState,
                 config: configPda,
-                memberState: user1MemberState,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                systemProgram: SystemProgram.programId,
             })
             .signers([user1])
             .rpc()
@@ -615,6 +1260,7 @@ describe("dao-voting-program", () => {
         await program.methods
             .unstakeTokens(remainingStake)
             .accounts({
+                rewardPool: rewardPoolPda,
                 owner: user1.publicKey,
                 ownerAta: user1Ata,
                 stakeAta: user1StakeAta,
@@ -622,10 +1268,7 @@ describe("dao-voting-program", () => {
                 mint: mintPda,
                 stakeState: user1StakeState,
                 config: configPda,
-                memberState: user1MemberState,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                systemProgram: SystemProgram.programId,
             })
             .signers([user1])
             .rpc()
@@ -642,6 +1285,7 @@ describe("dao-voting-program", () => {
                 stakeAuth: authPda,
                 mint: mintPda,
                 stakeState: user1StakeState,
+                memberState: user1MemberState,
                 config: configPda,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
@@ -687,6 +1331,9 @@ describe("dao-voting-program", () => {
                 proposal: proposalPda,
                 treasury: treasuryPda,
                 config: configPda,
+                auth: authPda,
+                mint: mintPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
             })
             .signers([user1])
@@ -741,7 +1388,8 @@ describe("dao-voting-program", () => {
                 "https://example.com/proposal2",
                 { vote: {} },
                 new BN(10),
-                new BN(100)
+                new BN(100),
+                { public: {} }
             )
             .accounts({
                 owner: user1.publicKey,
@@ -750,6 +1398,9 @@ describe("dao-voting-program", () => {
                 memberState: user1MemberState,
                 treasury: treasuryPda,
                 config: configPda,
+                auth: authPda,
+                mint: mintPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
             })
             .signers([user1])
@@ -807,6 +1458,7 @@ describe("dao-voting-program", () => {
         await program.methods
             .unstakeTokens(remainingStake)
             .accounts({
+                rewardPool: rewardPoolPda,
                 owner: user1.publicKey,
                 ownerAta: user1Ata,
                 stakeAta: user1StakeAta,
@@ -814,10 +1466,7 @@ describe("dao-voting-program", () => {
                 mint: mintPda,
                 stakeState: user1StakeState,
                 config: configPda,
-                memberState: user1MemberState,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                systemProgram: SystemProgram.programId,
             })
             .signers([user1])
             .rpc()
@@ -839,7 +1488,8 @@ describe("dao-voting-program", () => {
                     "https://example.com/proposal3",
                     { vote: {} },
                     new BN(10),
-                    new BN(100)
+                    new BN(100),
+                    { public: {} }
                 )
                 .accounts({
                     owner: user1.publicKey,
@@ -848,6 +1498,9 @@ describe("dao-voting-program", () => {
                     memberState: user1MemberState,
                     treasury: treasuryPda,
                     config: configPda,
+                    auth: authPda,
+                    mint: mintPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([user1])
@@ -856,4 +1509,1295 @@ describe("dao-voting-program", () => {
             "Should not be able to create a proposal without sufficient stake"
         );
     });
-});
\ No newline at end of file
+
+    it("Votes with weight from a voter-weight plugin", async () => {
+        const plugin = anchor.workspace.MockVoterWeightPlugin as Program<MockVoterWeightPlugin>;
+
+        // A second DAO that takes voting weight from the mock plugin instead of stake
+        const pluginSeed = new BN(randomBytes(8));
+        const [pluginConfig] = PublicKey.findProgramAddressSync(
+            [Buffer.from("config"), pluginSeed.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [pluginTreasury] = PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury"), pluginConfig.toBuffer()],
+            program.programId
+        );
+        const [pluginAuth] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auth"), pluginConfig.toBuffer()],
+            program.programId
+        );
+        const [pluginRewardPool] = PublicKey.findProgramAddressSync(
+            [Buffer.from("rewards"), pluginConfig.toBuffer()],
+            program.programId
+        );
+        const [pluginMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("mint"), pluginConfig.toBuffer()],
+            program.programId
+        );
+        const [stakeAta] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), pluginConfig.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), pluginConfig.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [memberState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("member"), pluginConfig.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+        const [voterWeightRecord] = PublicKey.findProgramAddressSync(
+            [Buffer.from("voter-weight"), pluginConfig.toBuffer(), user2.publicKey.toBuffer()],
+            plugin.programId
+        );
+        const ownerAta = getAssociatedTokenAddressSync(pluginMint, user2.publicKey);
+
+        await program.methods
+            .initialize(pluginSeed, daoSettings({ voterWeightPlugin: plugin.programId }))
+            .accounts({
+                rewardPool: pluginRewardPool,
+                initializer: provider.wallet.publicKey,
+                mint: pluginMint,
+                receiptMint: null,
+                config: pluginConfig,
+                treasury: pluginTreasury,
+                auth: pluginAuth,
+                tokenProgram,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc()
+            .then(confirm);
+
+        await program.methods
+            .issueTokens()
+            .accounts({
+                initializer: user2.publicKey,
+                initializerAta: ownerAta,
+                auth: pluginAuth,
+                treasury: pluginTreasury,
+                mint: pluginMint,
+                config: pluginConfig,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        await program.methods
+            .stakeTokens(new BN(0), new BN(1), new BN(0))
+            .accounts({
+                rewardPool: pluginRewardPool,
+                owner: user2.publicKey,
+                ownerAta,
+                stakeAta,
+                mint: pluginMint,
+                auth: pluginAuth,
+                stakeState,
+                memberState,
+                config: pluginConfig,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        // The plugin grants far more weight than the single staked token
+        await plugin.methods
+            .setVoterWeight(pluginConfig, new BN(500), null, null, null)
+            .accounts({
+                voter: user2.publicKey,
+                voterWeightRecord,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        const pluginProposalId = new BN(1);
+        const [pluginProposal] = PublicKey.findProgramAddressSync(
+            [Buffer.from("proposal"), pluginConfig.toBuffer(), pluginProposalId.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [pluginVote] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vote"), pluginProposal.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+
+        await program.methods
+            .createProposal(
+                pluginProposalId,
+                "Plugin Proposal",
+                "https://example.com/plugin",
+                { vote: {} },
+                new BN(1000),
+                new BN(100),
+                { public: {} }
+            )
+            .accounts({
+                owner: user2.publicKey,
+                stakeState,
+                proposal: pluginProposal,
+                memberState,
+                treasury: pluginTreasury,
+                config: pluginConfig,
+                voterWeightRecord,
+                auth: pluginAuth,
+                mint: pluginMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        await program.methods
+            .vote(new BN(500), { yes: {} })
+            .accounts({
+                owner: user2.publicKey,
+                stakeState,
+                proposal: pluginProposal,
+                vote: pluginVote,
+                memberState,
+                config: pluginConfig,
+                voterWeightRecord,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm)
+            .then(log);
+
+        const proposal = await program.account.proposal.fetch(pluginProposal);
+        assert.equal(proposal.yesVotes.toString(), "500", "Yes votes should use the plugin weight");
+    });
+
+    it("Mints and burns liquid staking receipts", async () => {
+        // A third DAO where staking mints transferable receipts
+        const liquidSeed = new BN(randomBytes(8));
+        const [liquidConfig] = PublicKey.findProgramAddressSync(
+            [Buffer.from("config"), liquidSeed.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [liquidTreasury] = PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury"), liquidConfig.toBuffer()],
+            program.programId
+        );
+        const [liquidAuth] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auth"), liquidConfig.toBuffer()],
+            program.programId
+        );
+        const [liquidRewardPool] = PublicKey.findProgramAddressSync(
+            [Buffer.from("rewards"), liquidConfig.toBuffer()],
+            program.programId
+        );
+        const [liquidMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("mint"), liquidConfig.toBuffer()],
+            program.programId
+        );
+        const [receiptMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("receipt"), liquidConfig.toBuffer()],
+            program.programId
+        );
+        const [stakeAta] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), liquidConfig.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), liquidConfig.toBuffer(), user2.publicKey.toBuffer(), new BN(0).toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeAuth] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auth"), liquidConfig.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+        const [memberState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("member"), liquidConfig.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+        const ownerAta = getAssociatedTokenAddressSync(liquidMint, user2.publicKey);
+        const receiptAccount = getAssociatedTokenAddressSync(receiptMint, user2.publicKey);
+
+        await program.methods
+            .initialize(liquidSeed, daoSettings({ liquidStaking: true }))
+            .accounts({
+                rewardPool: liquidRewardPool,
+                initializer: provider.wallet.publicKey,
+                mint: liquidMint,
+                receiptMint,
+                config: liquidConfig,
+                treasury: liquidTreasury,
+                auth: liquidAuth,
+                tokenProgram,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc()
+            .then(confirm);
+
+        await program.methods
+            .issueTokens()
+            .accounts({
+                initializer: user2.publicKey,
+                initializerAta: ownerAta,
+                auth: liquidAuth,
+                treasury: liquidTreasury,
+                mint: liquidMint,
+                config: liquidConfig,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        await createAssociatedTokenAccount(connection, user2, receiptMint, user2.publicKey);
+
+        await program.methods
+            .stakeTokens(new BN(0), new BN(10), new BN(0))
+            .accounts({
+                rewardPool: liquidRewardPool,
+                owner: user2.publicKey,
+                ownerAta,
+                stakeAta,
+                mint: liquidMint,
+                auth: stakeAuth,
+                stakeState,
+                memberState,
+                receiptMint,
+                receiptAccount,
+                daoAuth: liquidAuth,
+                config: liquidConfig,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        let receipts = await getAccount(connection, receiptAccount);
+        assert.equal(receipts.amount.toString(), "10", "Staking should mint one receipt per token");
+
+        // Wait for a new slot before unstaking
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+
+        await program.methods
+            .unstakeTokens(new BN(4))
+            .accounts({
+                rewardPool: liquidRewardPool,
+                owner: user2.publicKey,
+                ownerAta,
+                stakeAta,
+                auth: stakeAuth,
+                mint: liquidMint,
+                stakeState,
+                config: liquidConfig,
+                receiptMint,
+                receiptAccount,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        receipts = await getAccount(connection, receiptAccount);
+        assert.equal(receipts.amount.toString(), "6", "Unstaking should burn the receipts");
+    });
+
+    it("Votes with liquid staking receipts wherever they're held", async () => {
+        const dao = await createDao({ liquidStaking: true, minQuorum: new BN(1) });
+        await buyTokens(dao, user2);
+        const staker = await stakeTokens(dao, user2, new BN(0), new BN(10));
+
+        // user1 takes 4 receipts and votes with them from an empty position
+        const holderReceipts = await createAssociatedTokenAccount(connection, user1, dao.receiptMint, user1.publicKey);
+        await transfer(connection, user2, staker.receiptAccount, holderReceipts, user2, 4);
+        const holder = await stakeTokens(dao, user1, new BN(0), new BN(0));
+
+        const proposal = await propose(dao, staker);
+
+        // The staker only holds 6 receipts now
+        await assert.rejects(castVote(dao, staker, proposal, new BN(10)), /InsufficientStake/);
+        await castVote(dao, staker, proposal, new BN(6));
+        await castVote(dao, holder, proposal, new BN(4));
+
+        const { yesVotes } = await program.account.proposal.fetch(proposal);
+        assert.equal(yesVotes.toString(), "10", "Both receipt holders should have voted");
+
+        // Receipts backing an open vote can't move to another account to be voted with again
+        const frozen = await getAccount(connection, holderReceipts);
+        assert.ok(frozen.isFrozen, "Receipts voted with should be frozen");
+        await assert.rejects(transfer(connection, user1, holderReceipts, staker.receiptAccount, user1, 4));
+        await assert.rejects(
+            program.methods
+                .thawReceipts()
+                .accounts({
+                    owner: user1.publicKey,
+                    receiptAccount: holderReceipts,
+                    receiptMint: dao.receiptMint,
+                    memberState: holder.memberState,
+                    daoAuth: dao.auth,
+                    config: dao.config,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([user1])
+                .rpc(),
+            /AccountsOpen/
+        );
+    });
+
+    it("Thaws receipts once the vote on an executed proposal is cleaned up", async () => {
+        const dao = await createDao({ liquidStaking: true, minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const position = await stakeTokens(dao, user1, new BN(0), new BN(10));
+        const proposal = await propose(dao, position);
+        await castVote(dao, position, proposal, new BN(10));
+
+        // Executing the proposal closes it
+        await execute(dao, proposal, position);
+        assert.equal(await connection.getAccountInfo(proposal), null, "The proposal should be closed");
+
+        // The vote is still open until it's cleaned up
+        await assert.rejects(thawReceipts(dao, position), /AccountsOpen/);
+        await cleanupVote(dao, position, proposal);
+        await thawReceipts(dao, position);
+
+        const receipts = await getAccount(connection, position.receiptAccount);
+        assert.ok(!receipts.isFrozen, "The receipts should be transferable again");
+    });
+
+    it("Exits the DAO after cleaning up a vote on an executed proposal", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const position = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposal = await propose(dao, position);
+        await castVote(dao, position, proposal, new BN(100));
+        await execute(dao, proposal, position);
+
+        // The vote keeps the position open until it's cleaned up
+        await assert.rejects(exitDao(dao, position), /AccountsOpen/);
+        await cleanupVote(dao, position, proposal);
+        await exitDao(dao, position);
+
+        const tokens = await getAccount(connection, position.ownerAta);
+        assert.equal(tokens.amount.toString(), "100", "The member should get their stake back");
+        assert.equal(await connection.getAccountInfo(position.memberState), null, "The member state should be closed");
+    });
+
+    it("Partially unstakes a liquid position while its receipts back a vote", async () => {
+        const dao = await createDao({ liquidStaking: true, minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const position = await stakeTokens(dao, user1, new BN(0), new BN(10));
+        const proposal = await propose(dao, position);
+        await castVote(dao, position, proposal, new BN(6));
+
+        // Only the tokens above the 6 voted with can leave, and the receipts stay frozen
+        await unstake(dao, position, new BN(4));
+        await assert.rejects(unstake(dao, position, new BN(1)), /StakeCommitted/);
+        let receipts = await getAccount(connection, position.receiptAccount);
+        assert.equal(receipts.amount.toString(), "6", "The unstaked tokens' receipts should be burned");
+        assert.ok(receipts.isFrozen, "The receipts voted with should stay frozen");
+
+        // Cleaning up the vote on the executed proposal releases the rest
+        await execute(dao, proposal, position);
+        await cleanupVote(dao, position, proposal);
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+        await unstake(dao, position, new BN(6));
+        receipts = await getAccount(connection, position.receiptAccount);
+        assert.equal(receipts.amount.toString(), "0", "Every receipt should be burned");
+    });
+
+    it("Commits, reveals and penalizes unrevealed sealed votes", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        await buyTokens(dao, user2);
+        const revealer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const silent = await stakeTokens(dao, user2, new BN(0), new BN(100));
+        // Sealed for 10 slots, then revealed over 10 slots, slashing commits left unrevealed
+        const proposal = await propose(dao, revealer, { vote: {} }, new BN(10), { commitReveal: [new BN(10), true] });
+        await assert.rejects(castVote(dao, revealer, proposal, new BN(60)), /InvalidBallotMode/);
+
+        const salt = randomBytes(32);
+        await commitVote(dao, revealer, proposal, sealVote(user1.publicKey, 0, new BN(60), salt));
+        await commitVote(dao, silent, proposal, sealVote(user2.publicKey, 1, new BN(40), randomBytes(32)));
+        assert.equal((await program.account.stakeState.fetch(silent.stakeState)).committed.toString(), "100", "A commitment should lock the whole stake");
+
+        // Nothing can be revealed or discarded while votes are still sealed
+        await assert.rejects(revealVote(dao, revealer, proposal, new BN(60), { yes: {} }, salt), /NotInRevealWindow/);
+        await assert.rejects(cleanupCommit(dao, silent, proposal), /InvalidProposalStatus/);
+
+        // Once voting closes, only a reveal matching the commitment is counted
+        await new Promise((resolve) => setTimeout(resolve, 4500));
+        await assert.rejects(revealVote(dao, revealer, proposal, new BN(60), { yes: {} }, randomBytes(32)), /InvalidCommitment/);
+        await assert.rejects(revealVote(dao, revealer, proposal, new BN(100), { yes: {} }, salt), /InvalidCommitment/);
+        await revealVote(dao, revealer, proposal, new BN(60), { yes: {} }, salt);
+        const { yesVotes } = await program.account.proposal.fetch(proposal);
+        assert.equal(yesVotes.toString(), "60", "The revealed vote should be tallied");
+
+        // Reveals are rejected after the window, and the proposal can be executed
+        await new Promise((resolve) => setTimeout(resolve, 4500));
+        await assert.rejects(revealVote(dao, silent, proposal, new BN(40), { no: {} }, randomBytes(32)), /NotInRevealWindow/);
+        await execute(dao, proposal, revealer);
+        assert.equal(await connection.getAccountInfo(proposal), null, "The executed proposal should be closed");
+
+        // The unrevealed commitment can still be discarded, and is penalized
+        const commitment = pda(Buffer.from("commit"), proposal.toBuffer(), user2.publicKey.toBuffer());
+        const rent = await connection.getBalance(commitment);
+        const memberBefore = await program.account.memberState.fetch(silent.memberState);
+        const treasuryBefore = await connection.getBalance(dao.treasury);
+        await cleanupCommit(dao, silent, proposal);
+        const memberAfter = await program.account.memberState.fetch(silent.memberState);
+        const slashed = memberBefore.baseVotingPoints.sub(memberAfter.baseVotingPoints)
+            .add(memberAfter.forfeitedPoints.sub(memberBefore.forfeitedPoints));
+        assert.equal(slashed.toString(), "10", "The unrevealed commit should cost its base vote points");
+        assert.equal(await connection.getBalance(dao.treasury) - treasuryBefore, rent, "The commitment's rent should be forfeited to the treasury");
+        const stake = await program.account.stakeState.fetch(silent.stakeState);
+        assert.equal(stake.committed.toString(), "0", "The stake should be released");
+        assert.equal(stake.accounts.toString(), "0", "The commitment should no longer hold the stake");
+    });
+
+    it("Votes on several proposals in one batch", async () => {
+        const dao = await createDao();
+        await buyTokens(dao, user1);
+        const position = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposals = [await propose(dao, position), await propose(dao, position), await propose(dao, position)];
+        const voteOf = (proposal: PublicKey) => pda(Buffer.from("vote"), proposal.toBuffer(), user1.publicKey.toBuffer());
+        const batchVote = (items: Record<string, any>[], batch: PublicKey[]) =>
+            program.methods
+                .batchVote(items)
+                .accounts({
+                    owner: user1.publicKey,
+                    stakeState: position.stakeState,
+                    memberState: position.memberState,
+                    config: dao.config,
+                    receiptAccount: position.receiptAccount,
+                    systemProgram: SystemProgram.programId,
+                })
+                .remainingAccounts(
+                    batch.flatMap((proposal) => [
+                        { pubkey: proposal, isSigner: false, isWritable: true },
+                        { pubkey: voteOf(proposal), isSigner: false, isWritable: true },
+                    ])
+                )
+                .signers([user1])
+                .rpc()
+                .then(confirm);
+
+        await batchVote(
+            [
+                { amount: new BN(30), voteType: { yes: {} }, rationale: null },
+                { amount: new BN(40), voteType: { no: {} }, rationale: null },
+                { amount: new BN(50), voteType: { abstain: {} }, rationale: null },
+            ],
+            proposals
+        );
+        const tallies = await Promise.all(proposals.map((proposal) => program.account.proposal.fetch(proposal)));
+        assert.equal(tallies[0].yesVotes.toString(), "30", "The first proposal should get a Yes vote");
+        assert.equal(tallies[1].noVotes.toString(), "40", "The second proposal should get a No vote");
+        assert.equal(tallies[2].abstainVotes.toString(), "50", "The third proposal should get an Abstain vote");
+        const { amount } = await program.account.voteState.fetch(voteOf(proposals[1]));
+        assert.equal(amount.toString(), "40", "Each proposal should get its own vote account");
+        const { accounts } = await program.account.stakeState.fetch(position.stakeState);
+        assert.equal(accounts.toString(), "3", "Each vote should hold the position");
+
+        // A proposal can't be voted on twice in the same batch
+        const proposal = await propose(dao, position);
+        await assert.rejects(
+            batchVote(
+                [
+                    { amount: new BN(10), voteType: { yes: {} }, rationale: null },
+                    { amount: new BN(10), voteType: { yes: {} }, rationale: null },
+                ],
+                [proposal, proposal]
+            ),
+            /InvalidBatch/
+        );
+        assert.equal(await connection.getAccountInfo(voteOf(proposal)), null, "The rejected batch shouldn't leave a vote");
+    });
+
+    it("Votes with collection NFTs once per NFT, even after a transfer", async () => {
+        const collection = await mintNft(provider.wallet.publicKey);
+        const first = await mintNft(user1.publicKey, collection.mint);
+        const second = await mintNft(user1.publicKey, collection.mint);
+        const dao = await createDao({ nftCollection: collection.mint, nftVoteWeight: new BN(1), minQuorum: new BN(3) });
+        const proposal = await proposeWithNft(dao, user1, first);
+
+        // Each NFT of the collection adds one vote
+        await nftVote(dao, user1, proposal, [first, second]);
+        let { yesVotes } = await program.account.proposal.fetch(proposal);
+        assert.equal(yesVotes.toString(), "2", "Each NFT should add its weight once");
+        const record = await program.account.nftVoteRecord.fetch(nftVoteRecord(proposal, first));
+        assert.equal(record.voter.toBase58(), user1.publicKey.toBase58(), "The record should name the holder who voted");
+
+        // NFTs outside the collection don't count
+        const stranger = await mintNft(user2.publicKey);
+        await assert.rejects(nftVote(dao, user2, proposal, [stranger]), /InvalidNft/);
+
+        // Transferring an NFT doesn't let it vote again
+        const user2Account = await createAssociatedTokenAccount(connection, user2, first.mint, user2.publicKey);
+        await transfer(connection, user1, first.tokenAccount, user2Account, user1, 1);
+        const transferred = { ...first, tokenAccount: user2Account };
+        await assert.rejects(nftVote(dao, user2, proposal, [transferred]));
+        ({ yesVotes } = await program.account.proposal.fetch(proposal));
+        assert.equal(yesVotes.toString(), "2", "The transferred NFT shouldn't vote twice");
+
+        // Records are kept until the proposal can no longer be voted on
+        const cleanup = () =>
+            program.methods
+                .cleanupNftVote()
+                .accounts({ voter: user1.publicKey, proposal, nftVoteRecord: nftVoteRecord(proposal, first) })
+                .signers([user1])
+                .rpc()
+                .then(confirm);
+        await assert.rejects(cleanup(), /InvalidProposalStatus/);
+        await nftVote(dao, user2, proposal, [await mintNft(user2.publicKey, collection.mint)]);
+        await cleanup();
+        assert.equal(await connection.getAccountInfo(nftVoteRecord(proposal, first)), null, "The record should be closed");
+    });
+
+    it("Weighs NFTs by the weights their traits earn", async () => {
+        const collection = await mintNft(provider.wallet.publicKey);
+        const rare = await mintNft(user1.publicKey, collection.mint);
+        const common = await mintNft(user1.publicKey, collection.mint);
+        const dao = await createDao({
+            nftCollection: collection.mint,
+            nftVoteWeight: new BN(1),
+            nftWeightAuthority: admin.publicKey,
+            minQuorum: new BN(100),
+        });
+
+        // Only the weight authority records weights, for NFTs of the collection
+        await assert.rejects(setNftWeight(dao, user1, rare, new BN(5)), /InvalidNftWeightAuthority/);
+        await assert.rejects(setNftWeight(dao, admin, rare, new BN(0)), /InvalidNftWeight/);
+        await assert.rejects(setNftWeight(dao, admin, await mintNft(user1.publicKey), new BN(5)), /InvalidNft/);
+        await setNftWeight(dao, admin, rare, new BN(5));
+
+        // An NFT can't borrow another NFT's weight account to skip its own
+        const proposal = await proposeWithNft(dao, user1, rare);
+        await assert.rejects(nftVote(dao, user1, proposal, [rare], [nftWeight(dao, common)]), /InvalidNftWeight/);
+
+        // The weighed NFT counts its weight, the other the DAO's per-NFT weight
+        await nftVote(dao, user1, proposal, [rare, common]);
+        const { yesVotes } = await program.account.proposal.fetch(proposal);
+        assert.equal(yesVotes.toString(), "6", "The rare NFT should count 5 and the common one 1");
+    });
+
+    it("Records a vote's rationale and reads it back", async () => {
+        const dao = await createDao();
+        await buyTokens(dao, user1);
+        const position = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposal = await propose(dao, position);
+        const vote = pda(Buffer.from("vote"), proposal.toBuffer(), user1.publicKey.toBuffer());
+        const voteWithRationale = (uri: string) =>
+            program.methods
+                .voteWithRationale(new BN(40), { no: {} }, { uri, contentHash: Array.from(createHash("sha256").update(uri).digest()) })
+                .accounts({
+                    owner: user1.publicKey,
+                    stakeState: position.stakeState,
+                    proposal,
+                    vote,
+                    memberState: position.memberState,
+                    config: dao.config,
+                    receiptAccount: position.receiptAccount,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user1])
+                .rpc()
+                .then(confirm);
+
+        // Rationales are capped at 200 characters
+        await assert.rejects(voteWithRationale("x".repeat(201)), /InvalidRationale/);
+        const uri = "https://example.com/why-no";
+        await voteWithRationale(uri);
+
+        const view = await program.methods
+            .getVoteRationale()
+            .accounts({ voter: user1.publicKey, proposal, vote, config: dao.config })
+            .view();
+        assert.equal(view.voter.toBase58(), user1.publicKey.toBase58(), "The view should name the voter");
+        assert.equal(view.amount.toString(), "40", "The view should show the amount voted");
+        assert.ok(view.voteType.no, "The view should show the vote type");
+        assert.equal(view.rationale.uri, uri, "The rationale should be read back");
+        assert.deepEqual(view.rationale.contentHash, Array.from(createHash("sha256").update(uri).digest()), "The content hash should be read back");
+    });
+
+    it("Shares funded rewards between stakers pro rata", async () => {
+        const dao = await createDao();
+        await buyTokens(dao, user1);
+        await buyTokens(dao, user2);
+        const large = await stakeTokens(dao, user1, new BN(0), new BN(75));
+        const small = await stakeTokens(dao, user2, new BN(0), new BN(25));
+
+        // Governance moves 1 SOL from the treasury into the reward pool
+        const proposal = await propose(dao, large, { fundRewards: [new BN(LAMPORTS_PER_SOL)] });
+        await castVote(dao, large, proposal, new BN(75));
+        await castVote(dao, small, proposal, new BN(25));
+        await execute(dao, proposal, large, { rewardPool: dao.rewardPool });
+
+        const claim = async (position: Position) => {
+            const before = await connection.getBalance(position.owner.publicKey);
+            await program.methods
+                .claimRewards()
+                .accounts({
+                    owner: position.owner.publicKey,
+                    stakeState: position.stakeState,
+                    rewardPool: dao.rewardPool,
+                    config: dao.config,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([position.owner])
+                .rpc()
+                .then(confirm);
+            return (await connection.getBalance(position.owner.publicKey)) - before;
+        };
+        assert.equal(await claim(large), 0.75 * LAMPORTS_PER_SOL, "75 of 100 staked tokens should earn 75% of the rewards");
+        assert.equal(await claim(small), 0.25 * LAMPORTS_PER_SOL, "25 of 100 staked tokens should earn 25% of the rewards");
+        assert.equal(await claim(large), 0, "Rewards can only be claimed once");
+
+        const { totalDistributed, totalClaimed } = await program.account.rewardPool.fetch(dao.rewardPool);
+        assert.equal(totalClaimed.toString(), totalDistributed.toString(), "Every distributed lamport should be claimed");
+    });
+
+    it("Boosts votes by reputation in hybrid mode", async () => {
+        const issued = new BN(1_000_000);
+        const dao = await createDao({ issueAmount: issued, reputationCoefficientBps: 10000, minQuorum: new BN(10_000_000) });
+        await buyTokens(dao, user1);
+        await buyTokens(dao, user2);
+        const proposer = await stakeTokens(dao, user1, new BN(0), issued);
+        const newcomer = await stakeTokens(dao, user2, new BN(0), issued);
+
+        // Creating the proposal earns the proposer 5 reputation
+        const proposal = await propose(dao, proposer);
+        const { reputationScore } = await program.account.memberState.fetch(proposer.memberState);
+        assert.equal(reputationScore.toString(), "5", "Proposing should earn reputation");
+
+        // At a 100% coefficient, 5 of the 10000 maximum reputation adds 0.05% to the vote
+        await castVote(dao, proposer, proposal, issued);
+        await castVote(dao, newcomer, proposal, issued, { no: {} });
+        const { yesVotes, noVotes } = await program.account.proposal.fetch(proposal);
+        assert.equal(yesVotes.toString(), "1000500", "The proposer's reputation should boost their vote");
+        assert.equal(noVotes.toString(), "1000000", "A member without reputation should vote at face value");
+        const { amount, weight } = await program.account.voteState.fetch(
+            pda(Buffer.from("vote"), proposal.toBuffer(), user1.publicKey.toBuffer())
+        );
+        assert.equal(amount.toString(), "1000000", "The vote should record the tokens voted with");
+        assert.equal(weight.toString(), "1000500", "The vote should record the weight it was tallied with");
+    });
+
+    it("Returns the bond of a passed proposal and slashes the bond of a rejected one", async () => {
+        const dao = await createDao({ proposalBond: new BN(10), bondSlashBps: 5000 });
+        await buyTokens(dao, user1);
+        await buyTokens(dao, user2);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(50));
+        const voter = await stakeTokens(dao, user2, new BN(0), new BN(100));
+        const treasuryAta = await createAssociatedTokenAccountIdempotent(
+            connection, user1, dao.mint, dao.treasury, {}, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, true
+        );
+        const balance = async (account: PublicKey) => (await getAccount(connection, account)).amount.toString();
+        const bonded = async () => {
+            const { proposalCount } = await program.account.daoSetup.fetch(dao.config);
+            const id = proposalCount.addn(1);
+            const proposal = pda(Buffer.from("proposal"), dao.config.toBuffer(), id.toArrayLike(Buffer, "le", 8));
+            const bondEscrow = pda(Buffer.from("bond"), proposal.toBuffer());
+            await propose(dao, proposer, { vote: {} }, new BN(100), { public: {} }, dao.settings.minQuorum, {
+                ownerAta: proposer.ownerAta,
+                bondEscrow,
+            });
+            return { proposal, bondEscrow };
+        };
+        const bondAccounts = (bondEscrow: PublicKey) => ({ bondEscrow, proposerAta: proposer.ownerAta, treasuryAta });
+
+        // The bond is locked while the proposal is open and returned once it passes
+        const passed = await bonded();
+        assert.equal(await balance(proposer.ownerAta), "40", "The bond should leave the proposer's wallet");
+        assert.equal(await balance(passed.bondEscrow), "10", "The bond should be escrowed");
+        await castVote(dao, proposer, passed.proposal, new BN(50));
+        await castVote(dao, voter, passed.proposal, new BN(50));
+        await execute(dao, passed.proposal, proposer, bondAccounts(passed.bondEscrow));
+        assert.equal(await balance(proposer.ownerAta), "50", "The bond should be returned");
+        assert.equal(await connection.getAccountInfo(passed.bondEscrow), null, "The escrow should be closed");
+
+        // A proposal rejected by at least half the votes loses its bond to the treasury
+        const rejected = await bonded();
+        await castVote(dao, voter, rejected.proposal, new BN(100), { no: {} });
+        await program.methods
+            .cleanupProposal()
+            .accounts({
+                initializer: user1.publicKey,
+                payee: Keypair.generate().publicKey,
+                proposal: rejected.proposal,
+                proposerState: proposer.memberState,
+                treasury: dao.treasury,
+                config: dao.config,
+                auth: dao.auth,
+                mint: dao.mint,
+                ...bondAccounts(rejected.bondEscrow),
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user1])
+            .rpc()
+            .then(confirm);
+        assert.equal(await balance(proposer.ownerAta), "40", "The slashed bond shouldn't be returned");
+        assert.equal(await balance(treasuryAta), "10", "The slashed bond should go to the treasury");
+        assert.equal(await connection.getAccountInfo(rejected.bondEscrow), null, "The escrow should be closed");
+    });
+
+    it("Stakes tokens for another member with a lock", async () => {
+        const index = new BN(1);
+        const lockPeriod = new BN(1000);
+        const [stakeAta] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), configPda.toBuffer(), user2.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeAuth] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auth"), configPda.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+
+        await program.methods
+            .stakeFor(index, new BN(5), lockPeriod)
+            .accounts({
+                payer: user1.publicKey,
+                payerAta: user1Ata,
+                beneficiary: user2.publicKey,
+                stakeAta,
+                auth: stakeAuth,
+                mint: mintPda,
+                stakeState,
+                rewardPool: rewardPoolPda,
+                config: configPda,
+                memberState: user2MemberState,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user1])
+            .rpc()
+            .then(confirm);
+
+        const position = await program.account.stakeState.fetch(stakeState);
+        assert.equal(position.owner.toBase58(), user2.publicKey.toBase58(), "Position should belong to the beneficiary");
+        assert.equal(position.amount.toString(), "5", "Position should hold the granted tokens");
+        assert(position.lockUntil.gt(new BN(0)), "Position should be locked");
+    });
+
+    it("Rejects a locked position staked by someone else", async () => {
+        const index = new BN(1);
+        const [stakeAta] = PublicKey.findProgramAddressSync(
+            [Buffer.from("vault"), configPda.toBuffer(), user2.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeState] = PublicKey.findProgramAddressSync(
+            [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+            program.programId
+        );
+        const [stakeAuth] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auth"), configPda.toBuffer(), user2.publicKey.toBuffer()],
+            program.programId
+        );
+
+        // Locks can't be longer than the DAO allows
+        const longLockIndex = new BN(2);
+        await assert.rejects(
+            program.methods
+                .stakeFor(longLockIndex, new BN(5), new BN(100001))
+                .accounts({
+                    payer: user1.publicKey,
+                    payerAta: user1Ata,
+                    beneficiary: user2.publicKey,
+                    stakeAta: PublicKey.findProgramAddressSync(
+                        [Buffer.from("vault"), configPda.toBuffer(), user2.publicKey.toBuffer(), longLockIndex.toArrayLike(Buffer, "le", 8)],
+                        program.programId
+                    )[0],
+                    auth: stakeAuth,
+                    mint: mintPda,
+                    stakeState: PublicKey.findProgramAddressSync(
+                        [Buffer.from("stake"), configPda.toBuffer(), user2.publicKey.toBuffer(), longLockIndex.toArrayLike(Buffer, "le", 8)],
+                        program.programId
+                    )[0],
+                    rewardPool: rewardPoolPda,
+                    config: configPda,
+                    memberState: user2MemberState,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user1])
+                .rpc(),
+            /LockPeriodTooLong/
+        );
+
+        const before = await getAccount(connection, user1Ata);
+
+        // The beneficiary turns the locked position down, sending the tokens back to the payer
+        await program.methods
+            .rejectStake()
+            .accounts({
+                owner: user2.publicKey,
+                funder: user1.publicKey,
+                funderAta: user1Ata,
+                stakeAta,
+                stakeAuth,
+                mint: mintPda,
+                stakeState,
+                memberState: user2MemberState,
+                rewardPool: rewardPoolPda,
+                config: configPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([user2])
+            .rpc()
+            .then(confirm);
+
+        const after = await getAccount(connection, user1Ata);
+        assert.equal((after.amount - before.amount).toString(), "5", "The payer should get the tokens back");
+        assert.equal(await connection.getAccountInfo(stakeState), null, "The position should be closed");
+    });
+
+    it("Ragequits unstaked tokens for a share of the treasury", async () => {
+        const dao = await createDao();
+        await buyTokens(dao, user1);
+
+        const rent = await connection.getMinimumBalanceForRentExemption(0);
+        const before = await connection.getBalance(dao.treasury);
+        await ragequit(dao, user1, new BN(50));
+        const after = await connection.getBalance(dao.treasury);
+
+        // Half the supply takes half of what's above rent
+        assert.equal(before - after, Math.floor((before - rent) / 2), "The treasury should pay a pro-rata share");
+        const { amount } = await getAccount(connection, getAssociatedTokenAddressSync(dao.mint, user1.publicKey));
+        assert.equal(amount.toString(), "50", "The tokens should leave the holder's wallet");
+        const { value } = await connection.getTokenSupply(dao.mint);
+        assert.equal(value.amount, "50", "The tokens should be burned");
+    });
+
+    it("Doesn't pay out more SOL than the issue price of the tokens ragequit", async () => {
+        const dao = await createDao();
+
+        // The treasury holds more per token than the issue price, from an earlier holder and a donation
+        await buyTokens(dao, user1);
+        await donate(dao, user1, 3 * LAMPORTS_PER_SOL);
+
+        // Buying in and ragequitting straight away would otherwise take back 2.5 SOL for the 1 SOL paid
+        await buyTokens(dao, user2);
+        const before = await connection.getBalance(dao.treasury);
+        await ragequit(dao, user2, new BN(100));
+        const after = await connection.getBalance(dao.treasury);
+        assert.equal(before - after, dao.settings.issuePrice.toNumber(), "The payout should be capped at the issue price");
+    });
+
+    it("Keeps refundable proposal fees out of ragequits", async () => {
+        const fee = 0.1 * LAMPORTS_PER_SOL;
+        const dao = await createDao({ feeRefundPolicy: { refundOnSuccess: {} }, proposalFee: new BN(fee) });
+        await buyTokens(dao, user1);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposal = await propose(dao, proposer);
+        await buyTokens(dao, user2);
+        let { escrowedFees } = await program.account.daoSetup.fetch(dao.config);
+        assert.equal(escrowedFees.toNumber(), fee, "The fee should be escrowed while it may be refunded");
+
+        // Half the supply takes half of what's above rent and the escrowed fee
+        const rent = await connection.getMinimumBalanceForRentExemption(0);
+        const before = await connection.getBalance(dao.treasury);
+        await ragequit(dao, user2, new BN(100));
+        const after = await connection.getBalance(dao.treasury);
+        assert.equal(before - after, Math.floor((before - rent - fee) / 2), "The escrowed fee shouldn't be shared out");
+
+        // The fee is still there to refund once the proposal succeeds
+        await castVote(dao, proposer, proposal, new BN(100));
+        await execute(dao, proposal, proposer, { proposer: user1.publicKey });
+        ({ escrowedFees } = await program.account.daoSetup.fetch(dao.config));
+        assert.equal(escrowedFees.toNumber(), 0, "The fee should be released once the proposal is settled");
+    });
+
+    it("Stops ragequits while the guardian has paused payouts", async () => {
+        const guardian = Keypair.generate();
+        const dao = await createDao({ guardian: guardian.publicKey });
+        await buyTokens(dao, user1);
+
+        await program.methods
+            .pause({ payouts: {} }, new BN(1000))
+            .accounts({ guardian: guardian.publicKey, config: dao.config })
+            .signers([guardian])
+            .rpc()
+            .then(confirm);
+        await assert.rejects(ragequit(dao, user1, new BN(10)), /DaoPaused/);
+
+        // Only the guardian can pause or lift a pause
+        await assert.rejects(
+            program.methods
+                .unpause({ payouts: {} })
+                .accounts({ guardian: user1.publicKey, config: dao.config })
+                .signers([user1])
+                .rpc()
+        );
+        await program.methods
+            .unpause({ payouts: {} })
+            .accounts({ guardian: guardian.publicKey, config: dao.config })
+            .signers([guardian])
+            .rpc()
+            .then(confirm);
+        await ragequit(dao, user1, new BN(10));
+
+        const { amount } = await getAccount(connection, getAssociatedTokenAddressSync(dao.mint, user1.publicKey));
+        assert.equal(amount.toString(), "90", "The ragequit should go through once payouts resume");
+    });
+
+    it("Gives members roles through governance", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const member = await stakeTokens(dao, user1, new BN(0), new BN(100));
+
+        const ROLE_ADMIN = 1 << 0;
+        const proposal = await propose(dao, member, { setRoles: [user1.publicKey, ROLE_ADMIN] });
+        await castVote(dao, member, proposal, new BN(100));
+        await execute(dao, proposal, member, { roleMemberState: member.memberState });
+
+        const { roles } = await program.account.memberState.fetch(member.memberState);
+        assert.equal(roles, ROLE_ADMIN, "The member should be an admin");
+    });
+
+    it("Doesn't grant the council role without a council", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const member = await stakeTokens(dao, user1, new BN(0), new BN(100));
+
+        const ROLE_COUNCIL = 1 << 1;
+        await assert.rejects(propose(dao, member, { setRoles: [user1.publicKey, ROLE_COUNCIL] }), /InvalidRoles/);
+    });
+
+    it("Refunds the proposal fee when a proposal succeeds", async () => {
+        const dao = await createDao({ minQuorum: new BN(1), feeRefundPolicy: { refundOnSuccess: {} } });
+        await buyTokens(dao, user1);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposal = await propose(dao, proposer);
+        await castVote(dao, proposer, proposal, new BN(100));
+
+        // The refund needs somewhere to go
+        await assert.rejects(execute(dao, proposal, proposer, {}, user2), /MissingProposer/);
+
+        const before = await connection.getBalance(user1.publicKey);
+        await execute(dao, proposal, proposer, { proposer: user1.publicKey }, user2);
+        const after = await connection.getBalance(user1.publicKey);
+        assert.equal(after - before, dao.settings.proposalFee.toNumber(), "The proposer should get the fee back");
+    });
+
+    it("Pays a token bounty from a treasury vault", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const vault = await createTokenVault(dao, 100);
+        const payeeAccount = await createAssociatedTokenAccount(connection, user2, vault.mint, user2.publicKey);
+        const otherAccount = await createAssociatedTokenAccount(connection, user1, vault.mint, user1.publicKey);
+
+        const proposal = await propose(dao, proposer, { tokenBounty: [vault.mint, user2.publicKey, new BN(40)] });
+        await castVote(dao, proposer, proposal, new BN(100));
+
+        const accounts = {
+            treasuryVault: vault.treasuryVault,
+            tokenBountyMint: vault.mint,
+            vaultSpend: vault.vaultSpend,
+        };
+        // Only the payee's own token account can receive the bounty
+        await assert.rejects(
+            execute(dao, proposal, proposer, { ...accounts, payeeTokenAccount: otherAccount }),
+            /InvalidTokenBountyAccount/
+        );
+        await execute(dao, proposal, proposer, { ...accounts, payeeTokenAccount: payeeAccount });
+
+        const paid = await getAccount(connection, payeeAccount);
+        assert.equal(paid.amount.toString(), "40", "The payee should get the bounty");
+        const left = await getAccount(connection, vault.treasuryVault);
+        assert.equal(left.amount.toString(), "60", "The vault should pay the bounty");
+    });
+
+    it("Releases and abandons milestone bounties as the reviewer", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        await donate(dao, user1, 5 * LAMPORTS_PER_SOL);
+        const reviewer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+
+        const ROLE_REVIEWER = 1 << 3;
+        const grant = await propose(dao, reviewer, { setRoles: [user1.publicKey, ROLE_REVIEWER] });
+        await castVote(dao, reviewer, grant, new BN(100));
+        await execute(dao, grant, reviewer, { roleMemberState: reviewer.memberState });
+
+        const payee = Keypair.generate().publicKey;
+        const proposal = await propose(dao, reviewer, {
+            milestoneBounty: [payee, user1.publicKey, new BN(2 * LAMPORTS_PER_SOL), 2],
+        });
+        await castVote(dao, reviewer, proposal, new BN(100));
+        const milestoneBounty = pda(Buffer.from("milestone"), dao.config.toBuffer(), proposal.toBuffer());
+        await execute(dao, proposal, reviewer, { newMilestoneBounty: milestoneBounty });
+
+        const review = (member: Position) => ({
+            reviewer: member.owner.publicKey,
+            reviewerState: member.memberState,
+            payee,
+            milestoneBounty,
+            treasury: dao.treasury,
+            config: dao.config,
+        });
+
+        // Members without the reviewer role can't release tranches
+        await buyTokens(dao, user2);
+        const other = await stakeTokens(dao, user2, new BN(0), new BN(100));
+        await assert.rejects(
+            program.methods.releaseMilestone().accounts(review(other)).signers([user2]).rpc(),
+            /MissingRole/
+        );
+
+        await program.methods.releaseMilestone().accounts(review(reviewer)).signers([user1]).rpc().then(confirm);
+        assert.equal(await connection.getBalance(payee), LAMPORTS_PER_SOL, "The payee should get the first tranche");
+
+        // Abandoning the bounty returns the second tranche to the treasury
+        const before = await connection.getBalance(dao.treasury);
+        await program.methods.abandonMilestone().accounts(review(reviewer)).signers([user1]).rpc().then(confirm);
+        const after = await connection.getBalance(dao.treasury);
+        assert(after - before >= LAMPORTS_PER_SOL, "The treasury should get the unreleased tranche back");
+        assert.equal(await connection.getAccountInfo(milestoneBounty), null, "The bounty should be closed");
+    });
+
+    it("Streams SOL to a recipient until the stream is cancelled", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        await donate(dao, user1, 5 * LAMPORTS_PER_SOL);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+
+        // 1 SOL over 1000 slots
+        const recipient = Keypair.generate().publicKey;
+        const start = new BN(await connection.getSlot());
+        const end = start.addn(1000);
+        const proposal = await propose(dao, proposer, {
+            paymentStream: [recipient, null, new BN(LAMPORTS_PER_SOL / 1000), start, end, { slot: {} }],
+        });
+        await castVote(dao, proposer, proposal, new BN(100));
+        const paymentStream = pda(Buffer.from("stream"), dao.config.toBuffer(), proposal.toBuffer());
+        await execute(dao, proposal, proposer, { newPaymentStream: paymentStream });
+
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+        const withdraw = (to: PublicKey) =>
+            program.methods
+                .withdrawStream()
+                .accounts({
+                    recipient: to,
+                    paymentStream,
+                    treasury: dao.treasury,
+                    config: dao.config,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc()
+                .then(confirm);
+
+        // Vested funds only go to the stream's recipient
+        await assert.rejects(withdraw(user2.publicKey), /ConstraintAddress/);
+        await withdraw(recipient);
+        const paid = await connection.getBalance(recipient);
+        assert(paid > 0 && paid < LAMPORTS_PER_SOL, "The recipient should get what has vested so far");
+
+        const cancel = await propose(dao, proposer, { cancelStream: [paymentStream] });
+        await castVote(dao, proposer, cancel, new BN(100));
+        await execute(dao, cancel, proposer, { paymentStream });
+
+        const stream = await program.account.paymentStream.fetch(paymentStream);
+        assert(stream.end.lt(end), "The stream should stop vesting");
+    });
+
+    it("Splits a bounty between the recipients recorded before voting", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        await donate(dao, user1, 5 * LAMPORTS_PER_SOL);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const [first, second] = [Keypair.generate().publicKey, Keypair.generate().publicKey];
+
+        const createSplit = (proposal: PublicKey, shares: { recipient: PublicKey; value: BN }[]) =>
+            program.methods
+                .createBountySplit(shares)
+                .accounts({
+                    owner: user1.publicKey,
+                    proposal,
+                    bountySplit: pda(Buffer.from("split"), proposal.toBuffer()),
+                    config: dao.config,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user1])
+                .rpc()
+                .then(confirm);
+
+        const proposal = await propose(dao, proposer, { splitBounty: [new BN(2 * LAMPORTS_PER_SOL), { basisPoints: {} }] });
+        await assert.rejects(
+            createSplit(proposal, [
+                { recipient: first, value: new BN(6000) },
+                { recipient: second, value: new BN(3000) },
+            ]),
+            /InvalidBountySplit/
+        );
+        await createSplit(proposal, [
+            { recipient: first, value: new BN(6000) },
+            { recipient: second, value: new BN(4000) },
+        ]);
+        await castVote(dao, proposer, proposal, new BN(100));
+        await execute(
+            dao,
+            proposal,
+            proposer,
+            { bountySplit: pda(Buffer.from("split"), proposal.toBuffer()) },
+            user1,
+            [first, second].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        );
+
+        assert.equal(await connection.getBalance(first), 1.2 * LAMPORTS_PER_SOL, "The first recipient should get 60%");
+        assert.equal(await connection.getBalance(second), 0.8 * LAMPORTS_PER_SOL, "The second recipient should get 40%");
+
+        // Recipients can't be changed once voting has started
+        const late = await propose(dao, proposer, { splitBounty: [new BN(LAMPORTS_PER_SOL), { fixed: {} }] });
+        await castVote(dao, proposer, late, new BN(100));
+        await assert.rejects(createSplit(late, [{ recipient: first, value: new BN(LAMPORTS_PER_SOL) }]), /VotingStarted/);
+    });
+
+    it("Holds bounties to the spending limit unless a supermajority overrides it", async () => {
+        const dao = await createDao({
+            minQuorum: new BN(1),
+            maxSpendPerProposal: new BN(LAMPORTS_PER_SOL),
+            overrideThresholdBps: 9000,
+        });
+        await buyTokens(dao, user1);
+        await buyTokens(dao, user2);
+        await donate(dao, user1, 5 * LAMPORTS_PER_SOL);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const voter = await stakeTokens(dao, user2, new BN(0), new BN(100));
+        const payee = Keypair.generate().publicKey;
+
+        // 60% in favour isn't enough to go over the limit
+        const contested = await propose(dao, proposer, { bounty: [payee, new BN(2 * LAMPORTS_PER_SOL)] });
+        await castVote(dao, proposer, contested, new BN(60));
+        await castVote(dao, voter, contested, new BN(40), { no: {} });
+        await assert.rejects(execute(dao, contested, proposer, { payee }), /SpendingLimitExceeded/);
+
+        // A unanimous vote is
+        const unanimous = await propose(dao, proposer, { bounty: [payee, new BN(2 * LAMPORTS_PER_SOL)] });
+        await castVote(dao, proposer, unanimous, new BN(100));
+        await execute(dao, unanimous, proposer, { payee });
+        assert.equal(await connection.getBalance(payee), 2 * LAMPORTS_PER_SOL, "The override should pay the bounty");
+    });
+
+    it("Creates, funds and spends from a sub-treasury through governance", async () => {
+        const dao = await createDao({ minQuorum: new BN(1) });
+        await buyTokens(dao, user1);
+        await donate(dao, user1, 5 * LAMPORTS_PER_SOL);
+        const steward = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const pass = async (proposalType: Record<string, any>, accounts: Record<string, any> = {}, stewards?: PublicKey[]) => {
+            const proposal = await propose(dao, steward, proposalType);
+            const stewardSet = pda(Buffer.from("stewards"), proposal.toBuffer());
+            if (stewards) {
+                await program.methods
+                    .createStewardSet("grants", stewards)
+                    .accounts({
+                        owner: user1.publicKey,
+                        proposal,
+                        stewardSet,
+                        config: dao.config,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([user1])
+                    .rpc()
+                    .then(confirm);
+            }
+            await castVote(dao, steward, proposal, new BN(100));
+            await execute(dao, proposal, steward, { ...accounts, ...(stewards ? { stewardSet } : {}) });
+        };
+
+        const ROLE_STEWARD = 1 << 2;
+        await pass({ setRoles: [user1.publicKey, ROLE_STEWARD] }, { roleMemberState: steward.memberState });
+
+        // Members without the admin role can't create one directly
+        const subTreasury = pda(Buffer.from("sub_treasury"), dao.config.toBuffer(), Buffer.from("grants"));
+        await assert.rejects(
+            program.methods
+                .createSubTreasury("grants", [user1.publicKey])
+                .accounts({
+                    payer: user1.publicKey,
+                    payerState: steward.memberState,
+                    subTreasury,
+                    config: dao.config,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([user1])
+                .rpc(),
+            /MissingRole/
+        );
+
+        await pass({ createSubTreasury: {} }, { newSubTreasury: subTreasury }, [user1.publicKey]);
+        await pass({ fundSubTreasury: [subTreasury, new BN(LAMPORTS_PER_SOL), new BN(25)] }, { subTreasury });
+
+        const recipient = Keypair.generate().publicKey;
+        const spend = (amount: number) =>
+            program.methods
+                .spendSubTreasury(new BN(amount))
+                .accounts({
+                    steward: user1.publicKey,
+                    stewardState: steward.memberState,
+                    recipient,
+                    subTreasury,
+                    config: dao.config,
+                })
+                .signers([user1])
+                .rpc()
+                .then(confirm);
+        await spend(0.4 * LAMPORTS_PER_SOL);
+        await assert.rejects(spend(LAMPORTS_PER_SOL), /BudgetExceeded/);
+        assert.equal(await connection.getBalance(recipient), 0.4 * LAMPORTS_PER_SOL, "The steward should spend within the budget");
+
+        // Once the period ends, what's left goes back to the treasury
+        await new Promise((resolve) => setTimeout(resolve, 12000));
+        const before = await connection.getBalance(dao.treasury);
+        await program.methods
+            .returnSubTreasuryFunds()
+            .accounts({ subTreasury, treasury: dao.treasury, config: dao.config })
+            .rpc()
+            .then(confirm);
+        const after = await connection.getBalance(dao.treasury);
+        assert.equal(after - before, 0.6 * LAMPORTS_PER_SOL, "The unspent budget should be returned");
+
+        // Governance can hand the sub-treasury to new stewards
+        await pass({ setStewards: [subTreasury] }, { subTreasury }, [user2.publicKey]);
+        const { stewards } = await program.account.subTreasury.fetch(subTreasury);
+        assert.deepEqual(stewards.map((key) => key.toBase58()), [user2.publicKey.toBase58()], "The stewards should be replaced");
+    });
+
+    it("Lets the council veto a succeeded proposal during the veto window", async () => {
+        const councillor = Keypair.generate();
+        const dao = await createDao({ minQuorum: new BN(1), council: [councillor.publicKey], vetoWindow: new BN(1000) });
+        await buyTokens(dao, user1);
+        const proposer = await stakeTokens(dao, user1, new BN(0), new BN(100));
+        const proposal = await propose(dao, proposer);
+        await castVote(dao, proposer, proposal, new BN(100));
+
+        await program.methods.queueProposal().accounts({ proposal, config: dao.config }).rpc().then(confirm);
+        await assert.rejects(execute(dao, proposal, proposer), /VetoWindowOpen/);
+
+        const veto = (member: Keypair) =>
+            program.methods
+                .vetoProposal()
+                .accounts({ councilMember: member.publicKey, proposal, config: dao.config })
+                .signers([member])
+                .rpc()
+                .then(confirm);
+        await assert.rejects(veto(user2), /InvalidCouncilMember/);
+        await veto(councillor);
+
+        const { result } = await program.account.proposal.fetch(proposal);
+        assert.deepEqual(result, { vetoed: {} }, "The proposal should be vetoed");
+    });
+});